@@ -0,0 +1,72 @@
+"""
+Time and memory benchmarks for PageRank, once per solver method, and betweenness centrality.
+
+Run with ``python benchmarks/bench_centrality.py`` after building the extension in release mode
+(``make install-release``). Every case runs in a fresh process, so the reported peak memory is
+the growth of the resident set size while evaluating that expression alone. The numbers cover the
+current implementation only, there is no baseline to compare them with.
+"""
+
+import argparse
+import multiprocessing
+import resource
+import sys
+from typing import Tuple
+
+import polars as pl
+from bench_graph_solver import CASES, best_of
+
+from polars_grouper import betweenness_centrality, page_rank
+
+PAGE_RANK_METHODS = ["power", "push", "gauss_seidel", "parallel"]
+
+
+def peak_rss_mb() -> float:
+    """Peak resident set size of the current process, in megabytes."""
+    peak = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+    # Linux reports kilobytes, macOS bytes
+    return peak / 1024**2 if sys.platform == "darwin" else peak / 1024
+
+
+def run_case(case: str, expression: str, num_edges: int, repeat: int) -> Tuple[float, float]:
+    """Return the best time in seconds and the peak memory growth in megabytes of one case."""
+    df = CASES[case](num_edges)
+    expr = {
+        **{
+            f"page_rank[{method}]": page_rank(pl.col("from"), pl.col("to"), method=method)
+            for method in PAGE_RANK_METHODS
+        },
+        "betweenness_centrality": betweenness_centrality(pl.col("from"), pl.col("to")),
+    }[expression]
+
+    baseline = peak_rss_mb()
+    seconds = best_of(df, expr, repeat)
+    return seconds, peak_rss_mb() - baseline
+
+
+def main() -> None:
+    """Time page_rank and betweenness_centrality on every input shape."""
+    parser = argparse.ArgumentParser(description=__doc__)
+    parser.add_argument("--edges", type=int, default=1_000_000, help="Number of edges per PageRank input")
+    parser.add_argument(
+        "--betweenness-edges",
+        type=int,
+        default=5_000,
+        help="Number of edges per betweenness input, the algorithm is quadratic in the number of nodes",
+    )
+    parser.add_argument("--repeat", type=int, default=3, help="Number of runs per case")
+    args = parser.parse_args()
+
+    sizes = {f"page_rank[{method}]": args.edges for method in PAGE_RANK_METHODS}
+    sizes["betweenness_centrality"] = args.betweenness_edges
+
+    print(f"{'case':<16}{'expression':<26}{'edges':>10}{'seconds':>10}{'peak MB':>10}")
+    with multiprocessing.get_context("spawn").Pool(1, maxtasksperchild=1) as pool:
+        for case in CASES:
+            for expression, num_edges in sizes.items():
+                seconds, peak_mb = pool.apply(run_case, (case, expression, num_edges, args.repeat))
+                print(f"{case:<16}{expression:<26}{num_edges:>10}{seconds:>10.4f}{peak_mb:>10.1f}")
+
+
+if __name__ == "__main__":
+    main()
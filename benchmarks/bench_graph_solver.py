@@ -0,0 +1,85 @@
+"""
+Benchmarks for the component grouping expressions.
+
+Run with ``python benchmarks/bench_graph_solver.py`` after building the extension in release mode
+(``make install-release``). Each case is timed a few times and the best run is reported.
+"""
+
+import argparse
+import random
+import time
+from typing import Callable, Dict
+
+import polars as pl
+
+from polars_grouper import graph_component_summary, graph_solver
+
+
+def sorted_chain(num_edges: int) -> pl.DataFrame:
+    """Chain of sequential ids, every edge links a node to the next one."""
+    ids = [f"n{i:09d}" for i in range(num_edges + 1)]
+    return pl.DataFrame({"from": ids[:-1], "to": ids[1:]})
+
+
+def reversed_chain(num_edges: int) -> pl.DataFrame:
+    """Chain of sequential ids, every edge links a node to the previous one."""
+    ids = [f"n{i:09d}" for i in range(num_edges + 1)]
+    return pl.DataFrame({"from": ids[1:], "to": ids[:-1]})
+
+
+def star(num_edges: int) -> pl.DataFrame:
+    """A single hub connected to every other node."""
+    return pl.DataFrame({"from": ["hub"] * num_edges, "to": [f"n{i:09d}" for i in range(num_edges)]})
+
+
+def random_graph(num_edges: int, seed: int = 42) -> pl.DataFrame:
+    """Uniformly random edges between twice as many nodes as edges."""
+    rng = random.Random(seed)
+    num_nodes = 2 * num_edges
+    return pl.DataFrame(
+        {
+            "from": [f"n{rng.randrange(num_nodes):09d}" for _ in range(num_edges)],
+            "to": [f"n{rng.randrange(num_nodes):09d}" for _ in range(num_edges)],
+        }
+    )
+
+
+CASES: Dict[str, Callable[[int], pl.DataFrame]] = {
+    "sorted_chain": sorted_chain,
+    "reversed_chain": reversed_chain,
+    "star": star,
+    "random": random_graph,
+}
+
+
+def best_of(df: pl.DataFrame, expr: pl.Expr, repeat: int) -> float:
+    """Return the fastest of `repeat` runs of `expr` on `df`, in seconds."""
+    timings = []
+    for _ in range(repeat):
+        start = time.perf_counter()
+        df.select(expr)
+        timings.append(time.perf_counter() - start)
+    return min(timings)
+
+
+def main() -> None:
+    """Time graph_solver and graph_component_summary on every input shape."""
+    parser = argparse.ArgumentParser(description=__doc__)
+    parser.add_argument("--edges", type=int, default=1_000_000, help="Number of edges per input")
+    parser.add_argument("--repeat", type=int, default=3, help="Number of runs per case")
+    args = parser.parse_args()
+
+    expressions = {
+        "graph_solver": graph_solver(pl.col("from"), pl.col("to")),
+        "graph_component_summary": graph_component_summary(pl.col("from"), pl.col("to")),
+    }
+
+    print(f"{'case':<16}{'expression':<26}{'seconds':>10}")
+    for case, build in CASES.items():
+        df = build(args.edges)
+        for name, expr in expressions.items():
+            print(f"{case:<16}{name:<26}{best_of(df, expr, args.repeat):>10.4f}")
+
+
+if __name__ == "__main__":
+    main()
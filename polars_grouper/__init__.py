@@ -1,7 +1,7 @@
 from __future__ import annotations
 
 from pathlib import Path
-from typing import TYPE_CHECKING
+from typing import TYPE_CHECKING, Any, Literal, Sequence
 
 import polars as pl
 from polars.plugins import register_plugin_function
@@ -16,7 +16,22 @@ if TYPE_CHECKING:
 LIB = Path(__file__).parent
 
 
-def graph_solver(expr_from: IntoExpr, expr_to: IntoExpr) -> pl.Expr:
+WeightComparison = Literal[">=", ">", "<=", "<", "=="]
+NullPolicy = Literal["drop", "error", "isolate"]
+SelfLoops = Literal["keep", "drop"]
+ToleranceNorm = Literal["l1", "l2", "inf"]
+PageRankMethod = Literal["power", "push", "gauss_seidel", "parallel"]
+
+
+def graph_solver(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    weights: IntoExpr | None = None,
+    weight_threshold: float = 0.0,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
+) -> pl.Expr:
     """
     Identify connected components in a graph represented by edges.
 
@@ -29,6 +44,20 @@ def graph_solver(expr_from: IntoExpr, expr_to: IntoExpr) -> pl.Expr:
         Expression representing the source nodes of the edges.
     expr_to : IntoExpr
         Expression representing the destination nodes of the edges.
+    weights : IntoExpr, optional
+        Expression representing the edge weights. When given, only edges whose weight passes
+        `comparison` against `weight_threshold` are used to connect nodes.
+    weight_threshold : float, default 0.0
+        Threshold the edge weights are compared against. Ignored when `weights` is None.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of a
+        sequential number, so ids are reproducible across runs, row orderings and partitions.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
 
     Returns
     -------
@@ -63,20 +92,563 @@ def graph_solver(expr_from: IntoExpr, expr_to: IntoExpr) -> pl.Expr:
     Notes
     -----
     - The function treats the graph as undirected by default
-    - Component groups are assigned arbitrary but consistent numeric identifiers
+    - Component groups are numbered in order of first appearance, unless `stable_ids` is set
     - Isolated nodes will be assigned their own unique component
+    - Edges rejected by the weight threshold still assign their nodes a group, so every row
+      keeps a group id and nodes only touched by rejected edges end up in singleton groups
+    - Edges with a null weight are rejected
+    - Stable ids are 64-bit hashes, distinct components could in theory share an id
+    - Repeated edges and self-loops don't change the components
 
     """
     return register_plugin_function(
-        args=[expr_from, expr_to],
+        args=[expr_from, expr_to] + ([weights] if weights is not None else []),
         plugin_path=LIB,
         function_name="graph_solver",
         is_elementwise=False,
+        kwargs={
+            "weight_threshold": weight_threshold,
+            "comparison": comparison,
+            "stable_ids": stable_ids,
+            "null_policy": null_policy,
+        },
+    )
+
+
+def graph_solver_nodes(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    weights: IntoExpr | None = None,
+    weight_threshold: float = 0.0,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
+) -> pl.Expr:
+    """
+    Identify connected components and return the group of every distinct node.
+
+    Computes the same components as `graph_solver`, but instead of one group per input row it
+    returns one row per distinct node found in either column. This includes nodes that only ever
+    appear as a destination, which makes the result suitable for joining onto a node table.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the edges.
+    weights : IntoExpr, optional
+        Expression representing the edge weights. When given, only edges whose weight passes
+        `comparison` against `weight_threshold` are used to connect nodes.
+    weight_threshold : float, default 0.0
+        Threshold the edge weights are compared against. Ignored when `weights` is None.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of a
+        sequential number, so ids are reproducible across runs, row orderings and partitions.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per distinct node, containing:
+        - "node": node identifier
+        - "group": component group of the node, matching the groups returned by `graph_solver`
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> df = pl.DataFrame({
+    ...     "source": ["A", "B", "D"],
+    ...     "target": ["B", "C", "E"]
+    ... })
+    >>>
+    >>> result = df.select(
+    ...     graph_solver_nodes(pl.col("source"), pl.col("target")).alias("nodes")
+    ... ).unnest("nodes")
+    >>> print(result)
+    shape: (5, 2)
+    ┌──────┬───────┐
+    │ node ┆ group │
+    │ str  ┆ u64   │
+    ╞══════╪═══════╡
+    │ A    ┆ 1     │
+    │ B    ┆ 1     │
+    │ C    ┆ 1     │
+    │ D    ┆ 2     │
+    │ E    ┆ 2     │
+    └──────┴───────┘
+
+    Notes
+    -----
+    - Nodes are returned in order of first appearance in the input
+    - A row with a null source or destination never connects nodes. Under "drop" its non-null
+      node is only returned when it also appears in another row, under "isolate" it is always
+      returned and under "error" the row raises an error
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to] + ([weights] if weights is not None else []),
+        plugin_path=LIB,
+        function_name="graph_solver_nodes",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            "weight_threshold": weight_threshold,
+            "comparison": comparison,
+            "stable_ids": stable_ids,
+            "null_policy": null_policy,
+        },
+    )
+
+
+def graph_solver_incremental(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    state_node: IntoExpr,
+    state_group: IntoExpr,
+    weights: IntoExpr | None = None,
+    weight_threshold: float = 0.0,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
+) -> pl.Expr:
+    """
+    Update previously computed components with a batch of new edges.
+
+    The previous result is passed in as a node table, as returned by `graph_solver_nodes`, which
+    can be stored in any format Polars can write (e.g. Parquet or IPC). Only the new edges are
+    merged on top of it, so the full edge history doesn't have to be processed again.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the new edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the new edges.
+    state_node : IntoExpr
+        Nodes of the previous node table, usually a `pl.Series`.
+    state_group : IntoExpr
+        Groups of the previous node table, aligned with `state_node`.
+    weights : IntoExpr, optional
+        Expression representing the weights of the new edges. When given, only edges whose weight
+        passes `comparison` against `weight_threshold` are used to connect nodes.
+    weight_threshold : float, default 0.0
+        Threshold the edge weights are compared against. Ignored when `weights` is None.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of
+        keeping its previous id. Use this when the previous state was also built with stable ids.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per node of the previous state
+        and the new edges, containing:
+        - "node": node identifier
+        - "group": updated component group of the node
+        - "previous_group": group of the node in the previous state, null for new nodes
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> history = pl.DataFrame({"source": ["A", "C"], "target": ["B", "D"]})
+    >>> state = history.select(
+    ...     graph_solver_nodes(pl.col("source"), pl.col("target")).alias("nodes")
+    ... ).unnest("nodes")
+    >>>
+    >>> delta = pl.DataFrame({"source": ["B", "E"], "target": ["C", "F"]})
+    >>> result = delta.select(
+    ...     graph_solver_incremental(
+    ...         pl.col("source"), pl.col("target"), state["node"], state["group"]
+    ...     ).alias("nodes")
+    ... ).unnest("nodes")
+    >>> print(result)
+    shape: (6, 3)
+    ┌──────┬───────┬────────────────┐
+    │ node ┆ group ┆ previous_group │
+    │ str  ┆ u64   ┆ u64            │
+    ╞══════╪═══════╪════════════════╡
+    │ A    ┆ 1     ┆ 1              │
+    │ B    ┆ 1     ┆ 1              │
+    │ C    ┆ 1     ┆ 2              │
+    │ D    ┆ 1     ┆ 2              │
+    │ E    ┆ 3     ┆ null           │
+    │ F    ┆ 3     ┆ null           │
+    └──────┴───────┴────────────────┘
+
+    Notes
+    -----
+    - When components merge, the merged component keeps the smallest of their previous ids
+    - New components are numbered after the largest previous id
+    - Merged groups can be found by grouping on "group" and collecting the distinct "previous_group" values
+    - The result has the same layout as the state, so it can be stored as the next state
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to, state_node, state_group] + ([weights] if weights is not None else []),
+        plugin_path=LIB,
+        function_name="graph_solver_incremental",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            "weight_threshold": weight_threshold,
+            "comparison": comparison,
+            "stable_ids": stable_ids,
+            "null_policy": null_policy,
+        },
+    )
+
+
+def graph_component_lineage(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    state_node: IntoExpr,
+    state_group: IntoExpr,
+    weights: IntoExpr | None = None,
+    weight_threshold: float = 0.0,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
+) -> pl.Expr:
+    """
+    Report how previous component groups map onto the components of the current edges.
+
+    The components of the current edge list are recomputed and compared against a previous node
+    table, as returned by `graph_solver_nodes`. Because the full current edge list is used, both
+    merges caused by new edges and splits caused by removed edges are detected.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the current edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the current edges.
+    state_node : IntoExpr
+        Nodes of the previous node table, usually a `pl.Series`.
+    state_group : IntoExpr
+        Groups of the previous node table, aligned with `state_node`.
+    weights : IntoExpr, optional
+        Expression representing the edge weights. When given, only edges whose weight passes
+        `comparison` against `weight_threshold` are used to connect nodes.
+    weight_threshold : float, default 0.0
+        Threshold the edge weights are compared against. Ignored when `weights` is None.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, new groups are identified by a hash of their smallest node key instead of a
+        sequential number.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per (old_group, new_group) pair:
+        - "old_group": previous group, null for nodes that weren't in the previous state
+        - "new_group": current group as returned by `graph_solver`, null for nodes without current edges
+        - "num_nodes": number of nodes that moved from the old group to the new group
+        - "is_merge": whether the new group absorbed more than one old group
+        - "is_split": whether the old group was spread over more than one new group
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> state = pl.DataFrame({
+    ...     "node": ["A", "B", "C", "D", "X", "Y", "Z"],
+    ...     "group": [1, 1, 2, 2, 3, 3, 3]
+    ... })
+    >>> edges = pl.DataFrame({
+    ...     "source": ["A", "B", "C", "X", "Z"],
+    ...     "target": ["B", "C", "D", "Y", "W"]
+    ... })
+    >>>
+    >>> result = edges.select(
+    ...     graph_component_lineage(
+    ...         pl.col("source"), pl.col("target"), state["node"], state["group"]
+    ...     ).alias("lineage")
+    ... ).unnest("lineage")
+    >>> print(result)
+    shape: (5, 5)
+    ┌───────────┬───────────┬───────────┬──────────┬──────────┐
+    │ old_group ┆ new_group ┆ num_nodes ┆ is_merge ┆ is_split │
+    │ u64       ┆ u64       ┆ u64       ┆ bool     ┆ bool     │
+    ╞═══════════╪═══════════╪═══════════╪══════════╪══════════╡
+    │ 1         ┆ 1         ┆ 2         ┆ true     ┆ false    │
+    │ 2         ┆ 1         ┆ 2         ┆ true     ┆ false    │
+    │ 3         ┆ 2         ┆ 2         ┆ false    ┆ true     │
+    │ 3         ┆ 3         ┆ 1         ┆ false    ┆ true     │
+    │ null      ┆ 3         ┆ 1         ┆ false    ┆ false    │
+    └───────────┴───────────┴───────────┴──────────┴──────────┘
+
+    Notes
+    -----
+    - Pass the full current edge list; to only add new edges use `graph_solver_incremental`
+    - Rows are sorted by old group and then new group, with nulls last
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to, state_node, state_group] + ([weights] if weights is not None else []),
+        plugin_path=LIB,
+        function_name="graph_component_lineage",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            "weight_threshold": weight_threshold,
+            "comparison": comparison,
+            "stable_ids": stable_ids,
+            "null_policy": null_policy,
+        },
+    )
+
+
+def graph_component_summary(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    weights: IntoExpr | None = None,
+    weight_threshold: float = 0.0,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
+) -> pl.Expr:
+    """
+    Summarize every connected component of a graph represented by edges.
+
+    Computes the same components as `graph_solver`, but returns one row per component
+    instead of one group id per input row.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the edges.
+    weights : IntoExpr, optional
+        Expression representing the edge weights. When given, only edges whose weight passes
+        `comparison` against `weight_threshold` are used to connect nodes.
+    weight_threshold : float, default 0.0
+        Threshold the edge weights are compared against. Ignored when `weights` is None.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of a
+        sequential number, so ids are reproducible across runs, row orderings and partitions.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per component, containing:
+        - "group": component id, matching the groups returned by `graph_solver`
+        - "num_nodes": number of distinct nodes in the component
+        - "num_edges": number of edges in the component
+        - "density": `2 * num_edges / (num_nodes * (num_nodes - 1))`, 0 for single-node components
+        - "representative": the first node of the component encountered in the input,
+          or its smallest node when `stable_ids` is set
+        - "is_tree": whether the component is connected by exactly `num_nodes - 1` edges
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> df = pl.DataFrame({
+    ...     "source": ["A", "B", "C", "D", "F"],
+    ...     "target": ["B", "C", "A", "E", "G"]
+    ... })
+    >>>
+    >>> result = df.select(
+    ...     graph_component_summary(pl.col("source"), pl.col("target")).alias("summary")
+    ... ).unnest("summary")
+    >>> print(result)
+    shape: (3, 6)
+    ┌───────┬───────────┬───────────┬─────────┬────────────────┬─────────┐
+    │ group ┆ num_nodes ┆ num_edges ┆ density ┆ representative ┆ is_tree │
+    │ u64   ┆ u64       ┆ u64       ┆ f64     ┆ str            ┆ bool    │
+    ╞═══════╪═══════════╪═══════════╪═════════╪════════════════╪═════════╡
+    │ 1     ┆ 3         ┆ 3         ┆ 1.0     ┆ A              ┆ false   │
+    │ 2     ┆ 2         ┆ 1         ┆ 1.0     ┆ D              ┆ true    │
+    │ 3     ┆ 2         ┆ 1         ┆ 1.0     ┆ F              ┆ true    │
+    └───────┴───────────┴───────────┴─────────┴────────────────┴─────────┘
+
+    Notes
+    -----
+    - Duplicate edges and self-loops are counted in `num_edges`, so they can raise the density above 1
+    - Edges rejected by the weight threshold are not counted, but their nodes still form components
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to] + ([weights] if weights is not None else []),
+        plugin_path=LIB,
+        function_name="graph_component_summary",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            "weight_threshold": weight_threshold,
+            "comparison": comparison,
+            "stable_ids": stable_ids,
+            "null_policy": null_policy,
+        },
+    )
+
+
+def strongly_connected_components(
+    expr_from: IntoExpr, expr_to: IntoExpr, null_policy: NullPolicy = "drop"
+) -> pl.Expr:
+    """
+    Identify strongly connected components in a directed graph represented by edges.
+
+    Unlike `graph_solver`, which treats edges as undirected, this function respects edge direction:
+    two nodes share a component only when each can be reached from the other.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the edges.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node is still part of the graph.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per input row, containing:
+        - "component": strongly connected component of the row's source node
+        - "in_cycle": whether the row's edge lies on a cycle
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> df = pl.DataFrame({
+    ...     "source": ["A", "B", "C", "C", "D"],
+    ...     "target": ["B", "C", "A", "D", "E"]
+    ... })
+    >>>
+    >>> # Find strongly connected components
+    >>> result = df.with_columns(
+    ...     strongly_connected_components(pl.col("source"), pl.col("target")).alias("scc")
+    ... ).unnest("scc")
+    >>> print(result)
+    shape: (5, 4)
+    ┌────────┬────────┬───────────┬──────────┐
+    │ source ┆ target ┆ component ┆ in_cycle │
+    │ str    ┆ str    ┆ u64       ┆ bool     │
+    ╞════════╪════════╪═══════════╪══════════╡
+    │ A      ┆ B      ┆ 1         ┆ true     │
+    │ B      ┆ C      ┆ 1         ┆ true     │
+    │ C      ┆ A      ┆ 1         ┆ true     │
+    │ C      ┆ D      ┆ 1         ┆ false    │
+    │ D      ┆ E      ┆ 2         ┆ false    │
+    └────────┴────────┴───────────┴──────────┘
+
+    Notes
+    -----
+    - An edge lies on a cycle when both endpoints are in the same component, self-loops included
+    - Repeated edges don't change the components
+    - Component ids are numbered in order of first appearance of their nodes, starting at 1
+    - With the default `null_policy`, rows with a null source or destination get component 0 and
+      are never part of a cycle
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to],
+        plugin_path=LIB,
+        function_name="graph_strongly_connected_components",
+        is_elementwise=False,
+        kwargs={"null_policy": null_policy},
+    )
+
+
+def connected_components(expr_from: IntoExpr, expr_to: IntoExpr, null_policy: NullPolicy = "drop") -> pl.Expr:
+    """
+    Identify weakly and strongly connected components in a single pass over the edges.
+
+    Equivalent to combining `graph_solver` and `strongly_connected_components`, but the node
+    identifiers are only hashed once, which is considerably faster on large edge lists.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the edges.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per input row, containing:
+        - "weak_component": component of the row's source node when edges are treated as undirected
+        - "strong_component": strongly connected component of the row's source node
+        - "component_size": number of nodes in the weak component
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> df = pl.DataFrame({
+    ...     "source": ["A", "B", "C", "C", "D", "F"],
+    ...     "target": ["B", "C", "A", "D", "E", "G"]
+    ... })
+    >>>
+    >>> result = df.with_columns(
+    ...     connected_components(pl.col("source"), pl.col("target")).alias("components")
+    ... ).unnest("components")
+    >>> print(result)
+    shape: (6, 5)
+    ┌────────┬────────┬────────────────┬──────────────────┬────────────────┐
+    │ source ┆ target ┆ weak_component ┆ strong_component ┆ component_size │
+    │ str    ┆ str    ┆ u64            ┆ u64              ┆ u64            │
+    ╞════════╪════════╪════════════════╪══════════════════╪════════════════╡
+    │ A      ┆ B      ┆ 1              ┆ 1                ┆ 5              │
+    │ B      ┆ C      ┆ 1              ┆ 1                ┆ 5              │
+    │ C      ┆ A      ┆ 1              ┆ 1                ┆ 5              │
+    │ C      ┆ D      ┆ 1              ┆ 1                ┆ 5              │
+    │ D      ┆ E      ┆ 1              ┆ 2                ┆ 5              │
+    │ F      ┆ G      ┆ 2              ┆ 4                ┆ 2              │
+    └────────┴────────┴────────────────┴──────────────────┴────────────────┘
+
+    Notes
+    -----
+    - Weak component ids match the groups returned by `graph_solver`
+    - Strong component ids match the components returned by `strongly_connected_components`
+    - With the default `null_policy`, rows with a null source or destination get 0 for every field
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to],
+        plugin_path=LIB,
+        function_name="graph_components",
+        is_elementwise=False,
+        kwargs={"null_policy": null_policy},
     )
 
 
 def calculate_shortest_path(
-    expr_from: IntoExpr, expr_to: IntoExpr, weights: IntoExpr, directed: bool = False
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    weights: IntoExpr,
+    directed: bool = False,
+    null_policy: NullPolicy = "drop",
+    dedupe_edges: bool = False,
+    self_loops: SelfLoops = "keep",
 ) -> pl.Expr:
     """
     Calculate the shortest paths between all pairs of nodes in a weighted graph.
@@ -94,6 +666,16 @@ def calculate_shortest_path(
         Expression representing the edge weights. Must be non-negative values.
     directed : bool, default False
         If True, treats the graph as directed. If False, treats edges as bidirectional.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node is still part of the graph.
+    dedupe_edges : bool, default False
+        If True, repeated edges between the same pair of nodes are counted once, keeping the
+        smallest weight of every pair. Undirected graphs treat A→B and B→A as the same pair.
+    self_loops : {"keep", "drop"}, default "keep"
+        Whether edges from a node to itself are part of the graph. Dropped self-loops keep
+        their node in the graph.
 
     Returns
     -------
@@ -143,6 +725,8 @@ def calculate_shortest_path(
     - Weights must be non-negative
     - For undirected graphs, paths A→B and B→A will have the same distance
     - Memory usage scales with O(V²) where V is the number of vertices
+    - Repeated edges are alternatives, the path uses the lightest one, also with `dedupe_edges`
+    - Self-loops never shorten a path, so `self_loops` doesn't change the result
 
     """
     return register_plugin_function(
@@ -151,7 +735,12 @@ def calculate_shortest_path(
         function_name="graph_find_shortest_path",
         is_elementwise=False,
         changes_length=True,
-        kwargs={"directed": directed},
+        kwargs={
+            "directed": directed,
+            "null_policy": null_policy,
+            "dedupe_edges": dedupe_edges,
+            "self_loops": self_loops,
+        },
     )
 
 
@@ -161,13 +750,24 @@ def page_rank(
     damping_factor: float = 0.85,
     max_iterations: int = 100,
     convergence_threshold: float = 1e-6,
+    null_policy: NullPolicy = "drop",
+    dedupe_edges: bool = False,
+    self_loops: SelfLoops = "keep",
+    nodes: bool = False,
+    weights: IntoExpr | None = None,
+    personalization: IntoExpr | Sequence[Any] | None = None,
+    personalization_weights: IntoExpr | Sequence[float] | None = None,
+    partition_by: IntoExpr | None = None,
+    tolerance_norm: ToleranceNorm = "l1",
+    method: PageRankMethod = "power",
 ) -> pl.Expr:
     """
     Calculate PageRank scores for nodes in a graph.
 
     Implements the PageRank algorithm to compute importance scores for nodes in a graph
     based on the link structure. The algorithm was originally developed by Google"s founders
-    to rank web pages.
+    to rank web pages. With edge weights, a node passes its score to its neighbours in
+    proportion to the weight of the edges to them.
 
     Parameters
     ----------
@@ -181,15 +781,65 @@ def page_rank(
     max_iterations : int, default 100
         Maximum number of iterations for the algorithm to converge.
     convergence_threshold : float, default 1e-6
-        Minimum change in scores between iterations to consider the algorithm converged.
+        The iteration stops once the change in scores, measured with `tolerance_norm`, is below
+        this threshold.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node is still part of the graph.
+    dedupe_edges : bool, default False
+        If True, repeated edges between the same pair of nodes are counted once, keeping the
+        smallest weight of every pair.
+    self_loops : {"keep", "drop"}, default "keep"
+        Whether edges from a node to itself are part of the graph. Dropped self-loops keep
+        their node in the graph.
+    nodes : bool, default False
+        If True, return one row per distinct node instead of one score per input row.
+    weights : IntoExpr, optional
+        Expression representing the edge weights, which must be finite and non-negative. Rows
+        with a null weight don't add an edge, but their nodes are still ranked.
+    personalization : IntoExpr or sequence, optional
+        Seed nodes for personalized PageRank, as an expression or a list of nodes. Random jumps,
+        including those from nodes without outgoing edges, land on the seeds instead of on any
+        node, so the scores measure closeness to the seeds. Seeds that are not in the graph are
+        ignored.
+    personalization_weights : IntoExpr or sequence of float, optional
+        Finite, non-negative teleport weight of every seed, in the same order as
+        `personalization`. Seeds share the jumps equally when omitted.
+    partition_by : IntoExpr, optional
+        Expression representing a partition key. The edges of every partition form a separate
+        graph, and all of them are ranked in one pass, in parallel for large inputs. Rows with a
+        null key form a partition of their own. Can't be combined with `nodes` or
+        `personalization`.
+    tolerance_norm : {"l1", "l2", "inf"}, default "l1"
+        Norm of the change in scores between two iterations. "l1" sums the absolute changes of
+        all nodes, so it grows with the size of the graph, "l2" is their Euclidean length and
+        "inf" is the largest change of any single node.
+    method : {"power", "push", "gauss_seidel", "parallel"}, default "power"
+        Iteration that computes the scores, all of them converge to the same scores:
+
+        - "power": every node pulls the scores of the previous iteration over its incoming edges
+        - "push": every node pushes its score along its outgoing edges, with the share per edge
+          computed once instead of per edge and iteration, which helps on denser graphs
+        - "gauss_seidel": like "power", but scores are updated in place, so the rest of an
+          iteration already uses them. Usually needs fewer iterations
+        - "parallel": like "power", with the nodes split over threads. Slower than "power"
+          unless several cores are available
 
     Returns
     -------
     pl.Expr
-        A Polars expression that resolves to a struct containing:
-        - "node": node identifier
+        By default, a Polars expression that resolves to the PageRank score of the source node
+        of every row. With `nodes=True`, it resolves to a struct with one row per distinct node
+        containing:
+        - "node": node identifier, in the dtype of the input
         - "score": PageRank score for the node
-        - "iterations": number of iterations until convergence
+        - "in_degree": number of edges pointing to the node
+        - "out_degree": number of edges leaving the node
+        - "iterations": number of iterations that ran, the same for every node
+        - "residual": change in scores in the last iteration, measured with `tolerance_norm`
+        - "converged": whether the residual fell below `convergence_threshold` before
+          `max_iterations` was reached
 
     Examples
     --------
@@ -206,44 +856,195 @@ def page_rank(
     ...         pl.col("from"),
     ...         pl.col("to"),
     ...         damping_factor=0.85,
-    ...         max_iterations=50
+    ...         max_iterations=50,
+    ...         nodes=True
     ...     ).alias("pagerank")
     ... ).unnest("pagerank")
     >>>
     >>> print(result)
-    shape: (4, 2)
-    ┌──────┬───────────┐
-    │ node ┆ score     │
-    │ str  ┆ f64      │
-    ╞══════╪═══════════╡
-    │ A    ┆ 0.283019 │
-    │ B    ┆ 0.330189 │
-    │ C    ┆ 0.254717 │
-    │ D    ┆ 0.132075 │
-    └──────┴───────────┘
+    shape: (4, 7)
+    ┌──────┬──────────┬───────────┬────────────┬────────────┬───────────┬───────────┐
+    │ node ┆ score    ┆ in_degree ┆ out_degree ┆ iterations ┆ residual  ┆ converged │
+    │ ---  ┆ ---      ┆ ---       ┆ ---        ┆ ---        ┆ ---       ┆ ---       │
+    │ str  ┆ f64      ┆ u64       ┆ u64        ┆ u32        ┆ f64       ┆ bool      │
+    ╞══════╪══════════╪═══════════╪════════════╪════════════╪═══════════╪═══════════╡
+    │ A    ┆ 0.360274 ┆ 1         ┆ 2          ┆ 28         ┆ 9.6694e-7 ┆ true      │
+    │ B    ┆ 0.222492 ┆ 2         ┆ 1          ┆ 28         ┆ 9.6694e-7 ┆ true      │
+    │ C    ┆ 0.379734 ┆ 2         ┆ 1          ┆ 28         ┆ 9.6694e-7 ┆ true      │
+    │ D    ┆ 0.0375   ┆ 0         ┆ 1          ┆ 28         ┆ 9.6694e-7 ┆ true      │
+    └──────┴──────────┴───────────┴────────────┴────────────┴───────────┴───────────┘
 
     Notes
     -----
-    - The sum of all PageRank scores will be approximately 1.0
-    - Isolated nodes receive a minimum base score
+    - The sum of the PageRank scores of all nodes will be approximately 1.0
+    - Nodes without outgoing edges spread their score evenly over all nodes, like networkx and
+      igraph do, so sinks don't leak rank
+    - Nodes without incoming edges receive a minimum base score
     - Higher damping factors may require more iterations to converge
-    - The algorithm may not converge if max_iterations is too low
+    - The algorithm may not converge if max_iterations is too low, check the "converged" field
+      of `nodes=True`
+    - Every repeated edge is an extra out-link, so it moves more of its source's score to the
+      target; set `dedupe_edges` to count each link once
+    - A self-loop keeps part of a node's score on the node itself
+    - With weights, nodes whose outgoing edges all have weight 0 spread their score like nodes
+      without outgoing edges
+    - With `personalization`, at least one seed with a positive weight has to be in the graph
+    - With `partition_by`, the scores of every partition sum to 1.0, the same as
+      `page_rank(...).over(partition_by)` but without evaluating the expression per group
 
     """
+    if personalization_weights is not None and personalization is None:
+        raise ValueError("personalization_weights requires personalization")
+    if partition_by is not None and (nodes or personalization is not None):
+        raise ValueError("partition_by can't be combined with nodes or personalization")
+
+    if partition_by is not None:
+        return register_plugin_function(
+            args=[partition_by, expr_from, expr_to] + ([weights] if weights is not None else []),
+            plugin_path=LIB,
+            function_name="page_rank_grouped",
+            is_elementwise=False,
+            kwargs={
+                "damping_factor": damping_factor,
+                "max_iterations": max_iterations,
+                "convergence_threshold": convergence_threshold,
+                "null_policy": null_policy,
+                "dedupe_edges": dedupe_edges,
+                "self_loops": self_loops,
+                "weighted": weights is not None,
+                "tolerance_norm": tolerance_norm,
+                "method": method,
+            },
+        )
+
+    # Seed lists become literal series, they don't need the length of the edge columns
+    args = [expr_from, expr_to] + ([weights] if weights is not None else [])
+    for seed_input in (personalization, personalization_weights):
+        if isinstance(seed_input, (list, tuple)):
+            args.append(pl.lit(pl.Series(seed_input)))
+        elif seed_input is not None:
+            args.append(seed_input)
+
     return register_plugin_function(
-        args=[expr_from, expr_to],
+        args=args,
         plugin_path=LIB,
-        function_name="page_rank",
+        function_name="page_rank_nodes" if nodes else "page_rank",
         is_elementwise=False,
+        changes_length=nodes,
         kwargs={
             "damping_factor": damping_factor,
             "max_iterations": max_iterations,
             "convergence_threshold": convergence_threshold,
+            "null_policy": null_policy,
+            "dedupe_edges": dedupe_edges,
+            "self_loops": self_loops,
+            "weighted": weights is not None,
+            "tolerance_norm": tolerance_norm,
+            "method": method,
         },
     )
 
 
-def super_merger(df: DF, from_col_name: str, to_col_name: str) -> DF:
+def hits(
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    max_iterations: int = 100,
+    convergence_threshold: float = 1e-8,
+    null_policy: NullPolicy = "drop",
+    dedupe_edges: bool = False,
+    self_loops: SelfLoops = "keep",
+) -> pl.Expr:
+    """
+    Calculate HITS hub and authority scores for nodes in a directed graph.
+
+    Implements Kleinberg's Hyperlink-Induced Topic Search. A node is a good authority when good
+    hubs point to it, and a good hub when it points to good authorities, which suits bipartite
+    link graphs such as users linking to pages.
+
+    Parameters
+    ----------
+    expr_from : IntoExpr
+        Expression representing the source nodes of the edges.
+    expr_to : IntoExpr
+        Expression representing the destination nodes of the edges.
+    max_iterations : int, default 100
+        Maximum number of iterations for the algorithm to converge.
+    convergence_threshold : float, default 1e-8
+        The iteration stops once the hub scores change by less than this threshold in total.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node is still part of the graph.
+    dedupe_edges : bool, default False
+        If True, repeated edges between the same pair of nodes are counted once.
+    self_loops : {"keep", "drop"}, default "keep"
+        Whether edges from a node to itself are part of the graph. Dropped self-loops keep
+        their node in the graph.
+
+    Returns
+    -------
+    pl.Expr
+        A Polars expression that resolves to a struct with one row per distinct node containing:
+        - "node": node identifier, in the dtype of the input
+        - "hub": hub score of the node
+        - "authority": authority score of the node
+
+    Examples
+    --------
+    >>> import polars as pl
+    >>> # Users linking to pages
+    >>> df = pl.DataFrame({
+    ...     "from": ["u1", "u1", "u2", "u3", "u3"],
+    ...     "to": ["p1", "p2", "p1", "p1", "p3"]
+    ... })
+    >>>
+    >>> result = df.select(
+    ...     hits(pl.col("from"), pl.col("to")).alias("hits")
+    ... ).unnest("hits")
+    >>>
+    >>> print(result)
+    shape: (6, 3)
+    ┌──────┬──────────┬───────────┐
+    │ node ┆ hub      ┆ authority │
+    │ ---  ┆ ---      ┆ ---       │
+    │ str  ┆ f64      ┆ f64       │
+    ╞══════╪══════════╪═══════════╡
+    │ u1   ┆ 0.366025 ┆ 0.0       │
+    │ p1   ┆ 0.0      ┆ 0.57735   │
+    │ p2   ┆ 0.0      ┆ 0.211325  │
+    │ u2   ┆ 0.267949 ┆ 0.0       │
+    │ u3   ┆ 0.366025 ┆ 0.0       │
+    │ p3   ┆ 0.0      ┆ 0.211325  │
+    └──────┴──────────┴───────────┘
+
+    Notes
+    -----
+    - The hub scores and the authority scores each sum to 1.0, like networkx's `hits`
+    - Nodes without outgoing edges have a hub score of 0, nodes without incoming edges an
+      authority score of 0
+    - Every repeated edge counts again, so it adds more of its source's hub score to the
+      target; set `dedupe_edges` to count each link once
+
+    """
+    return register_plugin_function(
+        args=[expr_from, expr_to],
+        plugin_path=LIB,
+        function_name="hits",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            "max_iterations": max_iterations,
+            "convergence_threshold": convergence_threshold,
+            "null_policy": null_policy,
+            "dedupe_edges": dedupe_edges,
+            "self_loops": self_loops,
+        },
+    )
+
+
+def super_merger(
+    df: DF, from_col_name: str, to_col_name: str, stable_ids: bool = False, null_policy: NullPolicy = "drop"
+) -> DF:
     """
     Group nodes into connected components based on edge relationships.
 
@@ -258,6 +1059,13 @@ def super_merger(df: DF, from_col_name: str, to_col_name: str) -> DF:
         Name of the column containing source nodes.
     to_col_name : str
         Name of the column containing destination nodes.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of a
+        sequential number, so ids are reproducible across runs, row orderings and partitions.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
 
     Returns
     -------
@@ -294,20 +1102,31 @@ def super_merger(df: DF, from_col_name: str, to_col_name: str) -> DF:
     -----
     - Preserves all columns from the input DataFrame
     - Works with both eager (DataFrame) and lazy (LazyFrame) evaluation
-    - Group assignments are arbitrary but consistent integers
+    - Group assignments are sequential integers, or stable hashes when `stable_ids` is set
     - The function treats the graph as undirected
 
     """
-    return df.with_columns(graph_solver(pl.col(from_col_name), pl.col(to_col_name)).alias("group"))
+    return df.with_columns(
+        graph_solver(
+            pl.col(from_col_name), pl.col(to_col_name), stable_ids=stable_ids, null_policy=null_policy
+        ).alias("group")
+    )
 
 
 def super_merger_weighted(
-    df: DF, from_col_name: str, to_col_name: str, weighted_col_name: str, weight_threshold: float = 0.1
+    df: DF,
+    from_col_name: str,
+    to_col_name: str,
+    weighted_col_name: str,
+    weight_threshold: float = 0.1,
+    comparison: WeightComparison = ">=",
+    stable_ids: bool = False,
+    null_policy: NullPolicy = "drop",
 ) -> DF:
     """
     Group nodes into connected components considering edge weights.
 
-    Similar to super_merger, but only considers edges with weights above a specified threshold
+    Similar to super_merger, but only considers edges whose weight passes the threshold
     when identifying connected components.
 
     Parameters
@@ -321,13 +1140,21 @@ def super_merger_weighted(
     weighted_col_name : str
         Name of the column containing edge weights.
     weight_threshold : float, default 0.1
-        Minimum weight value for an edge to be considered in component identification.
+        Threshold an edge weight must pass for the edge to be considered in component identification.
+    comparison : {">=", ">", "<=", "<", "=="}, default ">="
+        How an edge weight is compared against `weight_threshold`.
+    stable_ids : bool, default False
+        If True, each component is identified by a hash of its smallest node key instead of a
+        sequential number, so ids are reproducible across runs, row orderings and partitions.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node still receives a group.
 
     Returns
     -------
     DF
-        Filtered DataFrame containing only edges above the weight threshold,
-        with an additional "group" column containing component assignments.
+        Input DataFrame with an additional "group" column containing component assignments.
 
     Examples
     --------
@@ -348,32 +1175,48 @@ def super_merger_weighted(
     ...     weight_threshold=0.3
     ... )
     >>> print(result)
-    shape: (3, 4)
+    shape: (5, 4)
     ┌──────┬──────┬────────┬───────┐
     │ from ┆ to   ┆ weight ┆ group │
-    │ str  ┆ str  ┆ f64    ┆ i64   │
+    │ str  ┆ str  ┆ f64    ┆ u64   │
     ╞══════╪══════╪════════╪═══════╡
     │ A    ┆ B    ┆ 0.9    ┆ 1     │
-    │ D    ┆ E    ┆ 0.8    ┆ 2     │
+    │ B    ┆ C    ┆ 0.2    ┆ 1     │
+    │ C    ┆ D    ┆ 0.05   ┆ 2     │
+    │ D    ┆ E    ┆ 0.8    ┆ 1     │
     │ E    ┆ A    ┆ 0.3    ┆ 1     │
     └──────┴──────┴────────┴───────┘
 
     Notes
     -----
-    - Filters out edges below the weight threshold before component identification
-    - Returns only rows corresponding to edges above the threshold
+    - The weight threshold is evaluated inside the solver, so all rows are kept
+    - Rows whose edge fails the threshold still receive the group of their source node
     - Works with both eager (DataFrame) and lazy (LazyFrame) evaluation
     - The function treats the graph as undirected
     - Weight threshold should be chosen based on the scale of your weight values
 
     """
-    return df.filter(pl.col(weighted_col_name) >= weight_threshold).with_columns(
-        graph_solver(pl.col(from_col_name), pl.col(to_col_name)).alias("group")
+    return df.with_columns(
+        graph_solver(
+            pl.col(from_col_name),
+            pl.col(to_col_name),
+            pl.col(weighted_col_name),
+            weight_threshold=weight_threshold,
+            comparison=comparison,
+            stable_ids=stable_ids,
+            null_policy=null_policy,
+        ).alias("group")
     )
 
 
 def betweenness_centrality(
-    expr_from: IntoExpr, expr_to: IntoExpr, normalized: bool = True, directed: bool = False
+    expr_from: IntoExpr,
+    expr_to: IntoExpr,
+    normalized: bool = True,
+    directed: bool = False,
+    null_policy: NullPolicy = "drop",
+    dedupe_edges: bool = False,
+    self_loops: SelfLoops = "keep",
 ) -> pl.Expr:
     """
     Calculate betweenness centrality for all nodes in a graph.
@@ -392,6 +1235,16 @@ def betweenness_centrality(
         and `1/((n-1)(n-2))` for directed graphs, where n is the number of nodes.
     directed : bool, default False
         If True, treats the graph as directed. If False, treats edges as bidirectional.
+    null_policy : {"drop", "error", "isolate"}, default "drop"
+        How rows with a null source or destination are handled. "drop" ignores the row, "error"
+        raises an error naming the row and "isolate" adds the non-null node
+        without an edge, so that node is still part of the graph.
+    dedupe_edges : bool, default False
+        If True, repeated edges between the same pair of nodes are counted once. Undirected
+        graphs treat A→B and B→A as the same pair.
+    self_loops : {"keep", "drop"}, default "keep"
+        Whether edges from a node to itself are part of the graph. Dropped self-loops keep
+        their node in the graph.
 
     Returns
     -------
@@ -440,6 +1293,10 @@ def betweenness_centrality(
     - Memory usage is O(|V| + |E|)
     - For large graphs, consider using approximate algorithms
     - Isolated nodes will have centrality of 0
+    - Every repeated edge is a distinct shortest path, so pairs connected through repeated edges
+      weigh more in the centrality of the nodes in between; set `dedupe_edges` to count each
+      link once
+    - Self-loops never lie on a shortest path, so `self_loops` doesn't change the result
 
     """
     return register_plugin_function(
@@ -448,7 +1305,13 @@ def betweenness_centrality(
         function_name="graph_betweenness_centrality",
         is_elementwise=False,
         changes_length=True,
-        kwargs={"normalized": normalized, "directed": directed},
+        kwargs={
+            "normalized": normalized,
+            "directed": directed,
+            "null_policy": null_policy,
+            "dedupe_edges": dedupe_edges,
+            "self_loops": self_loops,
+        },
     )
 
 
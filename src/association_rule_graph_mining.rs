@@ -1,5 +1,6 @@
 use crate::graph_utils::{
-    to_float64_chunked, to_int64_chunked, to_string_chunked, usize_to_t, AsUsize,
+    intern_nodes, node_dtype, nodes_by_id, to_float64_chunked, to_int64_chunked, usize_to_t,
+    with_id_type, with_node_columns, AsUsize, NodeColumn, NodeMap,
 };
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
@@ -9,7 +10,7 @@ use std::collections::HashSet;
 use std::convert::TryFrom;
 
 // New type definitions to reduce complexity
-type TransactionResult<T> = PolarsResult<(HashMap<String, T>, T, Vec<(i64, T, f64)>)>;
+type TransactionResult<K, T> = PolarsResult<(NodeMap<K, T>, T, Vec<(i64, T, f64)>)>;
 type ItemWithFreq = (usize, f64);
 type TransactionItems = Vec<ItemWithFreq>;
 
@@ -151,12 +152,13 @@ struct ItemMetrics {
     lift_score: f64,
 }
 
-fn process_transaction_data<T>(
+fn process_transaction_data<'a, C, T>(
     transaction_id: &Int64Chunked,
-    item_id: &StringChunked,
+    item_id: &'a C,
     frequency: Option<&Float64Chunked>,
-) -> TransactionResult<T>
+) -> TransactionResult<C::Key<'a>, T>
 where
+    C: NodeColumn,
     T: TryFrom<usize> + Copy + PartialEq + AsUsize,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
@@ -164,44 +166,32 @@ where
         Float64Chunked::full(PlSmallStr::from("frequency"), 1.0, transaction_id.len());
     let freq = frequency.unwrap_or(&default_freq);
 
-    let mut item_to_id = HashMap::new();
-    let mut transaction_items = Vec::new();
-    let mut id_counter: T = usize_to_t(0);
+    let mut item_to_id = NodeMap::default();
+    let mut id_counter: T = usize_to_t(0)?;
+    let item_ids = intern_nodes(item_id, &mut item_to_id, &mut id_counter)?;
 
-    let mut get_or_insert_id = |item: &str| -> T {
-        *item_to_id.entry(item.to_string()).or_insert_with(|| {
-            let id = id_counter;
-            id_counter = usize_to_t(id_counter.as_usize() + 1);
-            id
-        })
-    };
-
-    transaction_id
+    let transaction_items = transaction_id
         .iter()
-        .zip(item_id.iter())
+        .zip(item_ids)
         .zip(freq.iter())
-        .try_for_each(|((trans, item), freq)| -> PolarsResult<()> {
-            if let (Some(t), Some(i), Some(f)) = (trans, item, freq) {
-                let i_id = get_or_insert_id(i);
-                transaction_items.push((t, i_id, f));
-            }
-            Ok(())
-        })?;
+        .filter_map(|((trans, item), freq)| Some((trans?, item?, freq?)))
+        .collect();
 
     Ok((item_to_id, id_counter, transaction_items))
 }
 
-fn association_rule_output(_: &[Field]) -> PolarsResult<Field> {
+fn association_rule_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let item_type = node_dtype(&[input_fields[1].dtype()]);
     Ok(Field::new(
         PlSmallStr::from("association_rules"),
         DataType::Struct(vec![
-            Field::new(PlSmallStr::from("item"), DataType::String),
+            Field::new(PlSmallStr::from("item"), item_type.clone()),
             Field::new(PlSmallStr::from("support"), DataType::Float64),
             Field::new(PlSmallStr::from("lift_score"), DataType::Float64),
             Field::new(PlSmallStr::from("pattern"), DataType::UInt32),
             Field::new(
                 PlSmallStr::from("consequents"),
-                DataType::List(Box::new(DataType::String)),
+                DataType::List(Box::new(item_type)),
             ),
             Field::new(
                 PlSmallStr::from("confidence_scores"),
@@ -217,13 +207,34 @@ fn graph_association_rules(
     kwargs: AssociationRuleKwargs,
 ) -> PolarsResult<Series> {
     let transaction_id = to_int64_chunked(&inputs[0])?;
-    let item_id = to_string_chunked(&inputs[1])?;
     let frequency = inputs.get(2).map(to_float64_chunked).transpose()?;
 
-    type ItemId = u32;
+    with_node_columns!(&[&inputs[1]], |columns| {
+        let item_id = &columns[0];
+        with_id_type!(item_id.num_rows(), |ItemId| {
+            mine_association_rules::<_, ItemId>(
+                &transaction_id,
+                item_id,
+                frequency.as_ref(),
+                &kwargs,
+            )
+        })
+    })
+}
 
+fn mine_association_rules<C, T>(
+    transaction_id: &Int64Chunked,
+    item_id: &C,
+    frequency: Option<&Float64Chunked>,
+    kwargs: &AssociationRuleKwargs,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
     let (item_to_id, id_counter, transaction_items) =
-        process_transaction_data::<ItemId>(&transaction_id, &item_id, frequency.as_ref())?;
+        process_transaction_data::<C, T>(transaction_id, item_id, frequency)?;
 
     let num_items = id_counter.as_usize();
     let transaction_items: Vec<_> = transaction_items
@@ -231,12 +242,9 @@ fn graph_association_rules(
         .map(|(tid, iid, freq)| (tid, iid.as_usize(), freq))
         .collect();
 
-    let item_metrics = build_itemset_network(&transaction_items, num_items, &kwargs);
+    let item_metrics = build_itemset_network(&transaction_items, num_items, kwargs);
 
-    let id_to_item: HashMap<_, _> = item_to_id
-        .iter()
-        .map(|(k, &v)| (v.as_usize(), k.clone()))
-        .collect();
+    let id_to_item = nodes_by_id(&item_to_id);
 
     let total_transactions = transaction_items
         .iter()
@@ -255,28 +263,31 @@ fn graph_association_rules(
         if metrics.support_count > 0.0
             && (metrics.support_count / total_transactions) >= kwargs.min_support
         {
-            if let Some(item_name) = id_to_item.get(&id) {
-                let mut associations = metrics.associations.clone();
-                associations.sort_by(|(_, conf1), (_, conf2)| conf2.partial_cmp(conf1).unwrap());
-
-                let (consequent_items, confidences): (Vec<_>, Vec<_>) = associations
-                    .into_iter()
-                    .take(5)
-                    .map(|(iid, conf)| (id_to_item[&iid].clone(), conf))
-                    .unzip();
-
-                items.push(item_name.clone());
-                supports.push(metrics.support_count);
-                lift_scores.push(metrics.lift_score);
-                patterns.push(metrics.pattern_id as u32);
-                consequents.push(Series::new(PlSmallStr::from(""), consequent_items));
-                confidence_scores.push(Series::new(PlSmallStr::from(""), confidences));
-            }
+            let mut associations = metrics.associations.clone();
+            associations.sort_by(|(_, conf1), (_, conf2)| conf2.partial_cmp(conf1).unwrap());
+
+            let (consequent_items, confidences): (Vec<_>, Vec<_>) = associations
+                .into_iter()
+                .take(5)
+                .map(|(iid, conf)| (id_to_item[iid], conf))
+                .unzip();
+
+            items.push(id_to_item[id]);
+            supports.push(metrics.support_count);
+            lift_scores.push(metrics.lift_score);
+            patterns.push(u32::try_from(metrics.pattern_id).map_err(|_| {
+                polars_err!(
+                    ComputeError: "pattern id {} exceeds the UInt32 pattern column",
+                    metrics.pattern_id
+                )
+            })?);
+            consequents.push(item_id.nodes_to_series(PlSmallStr::from(""), &consequent_items)?);
+            confidence_scores.push(Series::new(PlSmallStr::from(""), confidences));
         }
     }
 
     let fields = vec![
-        Series::new("item".into(), items),
+        item_id.nodes_to_series("item".into(), &items)?,
         Series::new("support".into(), supports),
         Series::new("lift_score".into(), lift_scores),
         Series::new("pattern".into(), patterns),
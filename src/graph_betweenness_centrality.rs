@@ -1,55 +1,36 @@
-use crate::graph_utils::{to_string_chunked, usize_to_t, AsUsize};
+use crate::graph_utils::{
+    build_graph, node_dtype, with_id_type, with_node_columns, AsUsize, Csr, IngestOptions,
+    NodeColumn, NullPolicy, SelfLoops,
+};
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
 use serde::Deserialize;
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 use std::convert::TryFrom;
 
-// Type aliases to reduce complexity
-type NodeMap<T> = HashMap<String, T>;
-type EdgeList<T> = Vec<(T, T)>;
-type ProcessResult<T> = PolarsResult<(NodeMap<T>, T, EdgeList<T>)>;
-
 #[derive(Deserialize)]
 struct BetweennessCentralityKwargs {
     normalized: bool,
     directed: bool,
+    null_policy: NullPolicy,
+    dedupe_edges: bool,
+    self_loops: SelfLoops,
 }
 
-fn process_edges<T>(from: &StringChunked, to: &StringChunked) -> ProcessResult<T>
-where
-    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
-    <T as TryFrom<usize>>::Error: std::fmt::Debug,
-{
-    let estimated_size = from.len();
-    let mut node_to_id = HashMap::with_capacity(estimated_size);
-    let mut edges = Vec::with_capacity(estimated_size);
-    let mut id_counter: T = usize_to_t(0);
-
-    let mut get_or_insert_id = |node: &str| -> T {
-        *node_to_id.entry(node.to_string()).or_insert_with(|| {
-            let id = id_counter;
-            id_counter = usize_to_t(id_counter.as_usize() + 1);
-            id
-        })
-    };
-
-    from.iter()
-        .zip(to.iter())
-        .try_for_each(|(from_node, to_node)| -> PolarsResult<()> {
-            if let (Some(f), Some(t)) = (from_node, to_node) {
-                let f_id = get_or_insert_id(f);
-                let t_id = get_or_insert_id(t);
-                edges.push((f_id, t_id));
-            }
-            Ok(())
-        })?;
-
-    Ok((node_to_id, id_counter, edges))
+impl BetweennessCentralityKwargs {
+    fn ingest_options(&self) -> IngestOptions {
+        IngestOptions {
+            null_policy: self.null_policy,
+            dedupe_edges: self.dedupe_edges,
+            self_loops: self.self_loops,
+            directed: self.directed,
+        }
+    }
 }
 
 fn calculate_betweenness<T>(
-    adj_list: &[Vec<usize>],
+    adjacency: &Csr<T>,
+    incoming_adjacency: &Csr<T>,
     num_nodes: usize,
     normalized: bool,
     directed: bool,
@@ -60,13 +41,18 @@ where
 {
     let mut centrality = vec![0.0; num_nodes];
 
+    // Buffers are shared by all sources, so a source doesn't allocate per node
+    let mut stack = Vec::with_capacity(num_nodes);
+    let mut paths = vec![0; num_nodes];
+    let mut distances = vec![-1i32; num_nodes];
+    let mut queue = VecDeque::with_capacity(num_nodes);
+    let mut delta = vec![0.0; num_nodes];
+
     // For each node as source
     for source in 0..num_nodes {
-        let mut stack = Vec::new();
-        let mut paths = vec![0; num_nodes];
-        let mut distances = vec![-1i32; num_nodes];
-        let mut queue = VecDeque::new();
-        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); num_nodes];
+        paths.fill(0);
+        distances.fill(-1);
+        delta.fill(0.0);
 
         // BFS initialization
         paths[source] = 1;
@@ -77,7 +63,7 @@ where
         while let Some(v) = queue.pop_front() {
             stack.push(v);
 
-            for &w in &adj_list[v] {
+            for w in adjacency.neighbors(v).iter().map(|w| w.as_usize()) {
                 // Path discovery
                 if distances[w] < 0 {
                     queue.push_back(w);
@@ -87,15 +73,19 @@ where
                 // Path counting
                 if distances[w] == distances[v] + 1 {
                     paths[w] += paths[v];
-                    predecessors[w].push(v);
                 }
             }
         }
 
-        // Accumulation
-        let mut delta = vec![0.0; num_nodes];
+        // Accumulation, the predecessors of `w` are the incoming neighbours one step closer to
+        // the source
         while let Some(w) = stack.pop() {
-            for &v in &predecessors[w] {
+            let predecessors = incoming_adjacency
+                .neighbors(w)
+                .iter()
+                .map(|v| v.as_usize())
+                .filter(|&v| distances[v] >= 0 && distances[v] + 1 == distances[w]);
+            for v in predecessors {
                 let coeff = (paths[v] as f64 / paths[w] as f64) * (1.0 + delta[w]);
                 delta[v] += coeff;
             }
@@ -137,11 +127,14 @@ fn normalize_centrality(
     }
 }
 
-fn betweenness_centrality_output(_: &[Field]) -> PolarsResult<Field> {
+fn betweenness_centrality_output(input_fields: &[Field]) -> PolarsResult<Field> {
     Ok(Field::new(
         PlSmallStr::from("betweenness_centrality"),
         DataType::Struct(vec![
-            Field::new(PlSmallStr::from("node"), DataType::String),
+            Field::new(
+                PlSmallStr::from("node"),
+                node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]),
+            ),
             Field::new(PlSmallStr::from("centrality"), DataType::Float64),
         ]),
     ))
@@ -152,46 +145,39 @@ fn graph_betweenness_centrality(
     inputs: &[Series],
     kwargs: BetweennessCentralityKwargs,
 ) -> PolarsResult<Series> {
-    let from = to_string_chunked(&inputs[0])?;
-    let to = to_string_chunked(&inputs[1])?;
-    type NodeId = u32;
-
-    let (node_to_id, id_counter, edges) = process_edges::<NodeId>(&from, &to)?;
-    let num_nodes = id_counter.as_usize();
-
-    // Create adjacency list
-    let mut adj_list = vec![Vec::new(); num_nodes];
-    for (from_id, to_id) in edges {
-        adj_list[from_id.as_usize()].push(to_id.as_usize());
-        if !kwargs.directed {
-            adj_list[to_id.as_usize()].push(from_id.as_usize());
-        }
-    }
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_betweenness::<_, NodeId>(from, to, &kwargs)
+        })
+    })
+}
+
+fn process_betweenness<C, T>(
+    from: &C,
+    to: &C,
+    kwargs: &BetweennessCentralityKwargs,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = build_graph::<C, T>(from, to, None, kwargs.ingest_options())?;
+    let num_nodes = graph.num_nodes();
 
     // Calculate centrality
-    let centrality =
-        calculate_betweenness::<NodeId>(&adj_list, num_nodes, kwargs.normalized, kwargs.directed);
-
-    // Create reverse mapping for node IDs to names
-    let id_to_node: HashMap<_, _> = node_to_id
-        .iter()
-        .map(|(k, &v)| (v.as_usize(), k.clone()))
-        .collect();
-
-    // Build result vectors using iterator
-    let (nodes, centrality_values): (Vec<_>, Vec<_>) = centrality
-        .iter()
-        .enumerate()
-        .filter_map(|(i, &cent)| {
-            id_to_node
-                .get(&i)
-                .map(|node_name| (node_name.clone(), cent))
-        })
-        .unzip();
+    let centrality = calculate_betweenness::<T>(
+        &graph.adjacency(),
+        &graph.incoming_adjacency(),
+        num_nodes,
+        kwargs.normalized,
+        kwargs.directed,
+    );
 
     let fields = vec![
-        Series::new(PlSmallStr::from("node"), nodes),
-        Series::new(PlSmallStr::from("centrality"), centrality_values),
+        from.nodes_to_series(PlSmallStr::from("node"), &graph.nodes())?,
+        Series::new(PlSmallStr::from("centrality"), centrality),
     ];
 
     StructChunked::from_series(PlSmallStr::from("betweenness_centrality"), &fields)
@@ -1,14 +1,69 @@
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
+use rayon::prelude::*;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use std::borrow::Cow;
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::graph_utils::{process_edges, to_string_chunked, usize_to_t, AsUsize};
+use crate::graph_utils::{
+    bounded_id, extend_edges_with_filter, intern_nodes, node_dtype, nodes_by_id, process_edges,
+    process_edges_parallel, process_edges_with_filter, row_ranges, stable_hash, to_float64_chunked,
+    to_uint64_chunked, usize_to_t, with_id_type, with_node_columns, AsUsize, NodeColumn, NodeKey,
+    NodeMap, NullPolicy,
+};
+use crate::strongly_connected_components::{number_components, tarjan_scc};
+
+// Number of rows above which `graph_solver` switches to the multi-threaded path
+const PARALLEL_THRESHOLD: usize = 1 << 20;
+
+#[derive(Deserialize, Clone, Copy)]
+enum WeightComparison {
+    #[serde(rename = ">=")]
+    GreaterEqual,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = "<=")]
+    LessEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "==")]
+    Equal,
+}
+
+impl WeightComparison {
+    fn passes(self, weight: f64, threshold: f64) -> bool {
+        match self {
+            WeightComparison::GreaterEqual => weight >= threshold,
+            WeightComparison::Greater => weight > threshold,
+            WeightComparison::LessEqual => weight <= threshold,
+            WeightComparison::Less => weight < threshold,
+            WeightComparison::Equal => weight == threshold,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GraphSolverKwargs {
+    weight_threshold: f64,
+    comparison: WeightComparison,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+}
+
+#[derive(Deserialize)]
+struct ComponentsKwargs {
+    null_policy: NullPolicy,
+}
 
 struct UnionFind<T>
 where
     T: Copy + PartialEq + AsUsize,
 {
     nodes: Vec<T>,
+    // Number of nodes in the tree of every root, only meaningful for roots
+    sizes: Vec<T>,
 }
 
 impl<T> UnionFind<T>
@@ -18,7 +73,8 @@ where
 {
     fn new(size: usize) -> Self {
         UnionFind {
-            nodes: (0..size).map(|i| usize_to_t(i)).collect(),
+            nodes: (0..size).map(|i| bounded_id(i)).collect(),
+            sizes: vec![bounded_id(1); size],
         }
     }
 
@@ -32,41 +88,36 @@ where
         x
     }
 
+    // Union by size: the smaller tree is attached below the root of the larger one, which keeps
+    // the trees shallow for chain-shaped inputs
     #[inline(always)]
     fn union(&mut self, x: T, y: T) {
-        let root_x = self.find(x);
-        let root_y = self.find(y);
+        let mut root_x = self.find(x);
+        let mut root_y = self.find(y);
         if root_x != root_y {
+            if self.sizes[root_x.as_usize()].as_usize() < self.sizes[root_y.as_usize()].as_usize() {
+                std::mem::swap(&mut root_x, &mut root_y);
+            }
             self.nodes[root_y.as_usize()] = root_x;
+            self.sizes[root_x.as_usize()] = bounded_id(
+                self.sizes[root_x.as_usize()].as_usize() + self.sizes[root_y.as_usize()].as_usize(),
+            );
         }
     }
-}
-
-#[polars_expr(output_type = UInt64)]
-fn graph_solver(inputs: &[Series]) -> PolarsResult<Series> {
-    let from = to_string_chunked(&inputs[0])?;
-    let to = to_string_chunked(&inputs[1])?;
-
-    let len = from.len();
 
-    if len <= u16::MAX as usize {
-        process_graph::<u16>(&from, &to)
-    } else if len <= u32::MAX as usize {
-        process_graph::<u32>(&from, &to)
-    } else {
-        process_graph::<u64>(&from, &to)
+    #[inline(always)]
+    fn component_size(&mut self, x: T) -> usize {
+        let root = self.find(x);
+        self.sizes[root.as_usize()].as_usize()
     }
 }
 
-fn process_graph<T>(from: &StringChunked, to: &StringChunked) -> PolarsResult<Series>
+fn union_edges<T>(num_nodes: usize, edges: &[(T, T)]) -> UnionFind<T>
 where
-    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    let (node_to_id, id_counter, edges) = process_edges::<T>(from, to)?;
-
     // Initialize the UnionFind structure with the number of nodes
-    let num_nodes = id_counter.as_usize();
     let mut uf = UnionFind::new(num_nodes);
 
     // Process the edges with union-find
@@ -74,30 +125,811 @@ where
         uf.union(f_id, t_id);
     });
 
+    uf
+}
+
+// Lock-free union-find for the multi-threaded path. A root is always linked below the smaller
+// root, so the root of a component is its smallest node id regardless of the order of the unions
+struct ConcurrentUnionFind {
+    nodes: Vec<AtomicUsize>,
+}
+
+impl ConcurrentUnionFind {
+    fn new(size: usize) -> Self {
+        ConcurrentUnionFind {
+            nodes: (0..size).map(AtomicUsize::new).collect(),
+        }
+    }
+
+    #[inline(always)]
+    fn find(&self, mut x: usize) -> usize {
+        loop {
+            let parent = self.nodes[x].load(Ordering::Relaxed);
+            if parent == x {
+                return x;
+            }
+            let grandparent = self.nodes[parent].load(Ordering::Relaxed);
+            if parent != grandparent {
+                // Path halving, losing the race to another thread is harmless
+                let _ = self.nodes[x].compare_exchange_weak(
+                    parent,
+                    grandparent,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                );
+            }
+            x = grandparent;
+        }
+    }
+
+    #[inline(always)]
+    fn union(&self, x: usize, y: usize) {
+        let (mut x, mut y) = (x, y);
+        loop {
+            x = self.find(x);
+            y = self.find(y);
+            if x == y {
+                return;
+            }
+            if x < y {
+                std::mem::swap(&mut x, &mut y);
+            }
+            // Retry when another thread attached x somewhere in the meantime
+            if self.nodes[x]
+                .compare_exchange(x, y, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+}
+
+// Multi-threaded version of `connected_components` with identical output
+fn connected_components_parallel<T>(num_nodes: usize, edges: &[(T, T)]) -> Vec<T>
+where
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Send + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let uf = ConcurrentUnionFind::new(num_nodes);
+    edges
+        .par_iter()
+        .with_min_len(4096)
+        .for_each(|&(f_id, t_id)| uf.union(f_id.as_usize(), t_id.as_usize()));
+
+    let roots: Vec<usize> = (0..num_nodes)
+        .into_par_iter()
+        .with_min_len(4096)
+        .map(|id| uf.find(id))
+        .collect();
+
+    // Roots are the smallest id of their component, so they are visited before the other
+    // members and groups are numbered by their first node just like `dense_group_ids`
+    let mut group_ids = vec![bounded_id(0); num_nodes];
+    let mut group_counter = 1;
+    for (id, &root) in roots.iter().enumerate() {
+        if root == id {
+            group_ids[id] = bounded_id(group_counter);
+            group_counter += 1;
+        } else {
+            group_ids[id] = group_ids[root];
+        }
+    }
+
+    group_ids
+}
+
+// Union all edges and number the resulting components by the order in which
+// their first node was seen, starting at 1
+fn connected_components<T>(num_nodes: usize, edges: &[(T, T)]) -> Vec<T>
+where
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let mut uf = union_edges(num_nodes, edges);
+    dense_group_ids(&mut uf, num_nodes)
+}
+
+fn dense_group_ids<T>(uf: &mut UnionFind<T>, num_nodes: usize) -> Vec<T>
+where
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
     // Initialize group IDs and counters
-    let mut group_ids = vec![usize_to_t(0); num_nodes];
-    let mut group_counter: T = usize_to_t(1);
+    let mut group_ids = vec![bounded_id(0); num_nodes];
+    // Kept as usize, after the last group it can exceed the range of `T`
+    let mut group_counter = 1;
 
     // Assign group IDs
-    for id in (0..num_nodes).map(|i| usize_to_t(i)) {
+    for id in (0..num_nodes).map(|i| bounded_id(i)) {
         let root = uf.find(id);
-        if group_ids[root.as_usize()] == usize_to_t(0) {
-            group_ids[root.as_usize()] = group_counter;
-            group_counter = usize_to_t(group_counter.as_usize() + 1);
+        if group_ids[root.as_usize()] == bounded_id(0) {
+            group_ids[root.as_usize()] = bounded_id(group_counter);
+            group_counter += 1;
         }
         group_ids[id.as_usize()] = group_ids[root.as_usize()];
     }
 
+    group_ids
+}
+
+// The id of the node with the smallest key in every group, indexed by dense group id - 1. Keys
+// are compared as strings, so stable ids don't depend on the dtype of the node columns
+fn min_node_per_group<T>(group_ids: &[T], node_keys: &[Cow<str>]) -> Vec<usize>
+where
+    T: Copy + AsUsize,
+{
+    let num_groups = group_ids.iter().map(|g| g.as_usize()).max().unwrap_or(0);
+    let mut min_nodes: Vec<Option<usize>> = vec![None; num_groups];
+
+    for (id, group) in group_ids.iter().enumerate() {
+        let min_node = &mut min_nodes[group.as_usize() - 1];
+        if min_node.is_none_or(|current| node_keys[id] < node_keys[current]) {
+            *min_node = Some(id);
+        }
+    }
+
+    min_nodes.into_iter().flatten().collect()
+}
+
+// Converts the dense group ids to the output ids. With `stable_ids` a group is identified by a
+// hash of its smallest node key, which doesn't depend on row order or on the other components
+fn resolve_group_ids<K, T>(
+    group_ids: Vec<T>,
+    node_to_id: &NodeMap<K, T>,
+    stable_ids: bool,
+) -> Vec<u64>
+where
+    K: NodeKey,
+    T: Copy + AsUsize + Into<u64>,
+{
+    if !stable_ids {
+        return group_ids.into_iter().map(Into::into).collect();
+    }
+
+    let node_keys: Vec<Cow<str>> = nodes_by_id(node_to_id)
+        .into_iter()
+        .map(NodeKey::key_str)
+        .collect();
+    let group_hashes: Vec<u64> = min_node_per_group(&group_ids, &node_keys)
+        .into_iter()
+        .map(|id| stable_hash(&node_keys[id]))
+        .collect();
+
+    group_ids
+        .iter()
+        .map(|group| group_hashes[group.as_usize() - 1])
+        .collect()
+}
+
+// Rows with a null weight or a weight failing the comparison don't contribute an edge. The
+// weights need one value per edge row, a scalar weight is rejected
+fn weight_mask(
+    from: &Series,
+    weights: Option<&Series>,
+    kwargs: &GraphSolverKwargs,
+) -> PolarsResult<Option<Vec<bool>>> {
+    if let Some(weights) = weights {
+        polars_ensure!(
+            weights.len() == from.len(),
+            ShapeMismatch: "weights and edges differ in length: {} != {}",
+            weights.len(),
+            from.len()
+        );
+    }
+    let weights = weights.map(to_float64_chunked).transpose()?;
+
+    Ok(weights.map(|weights| {
+        weights
+            .iter()
+            .map(|weight| {
+                weight.is_some_and(|w| kwargs.comparison.passes(w, kwargs.weight_threshold))
+            })
+            .collect()
+    }))
+}
+
+#[polars_expr(output_type = UInt64)]
+fn graph_solver(inputs: &[Series], kwargs: GraphSolverKwargs) -> PolarsResult<Series> {
+    let edge_mask = weight_mask(&inputs[0], inputs.get(2), &kwargs)?;
+    let edge_mask = edge_mask.as_deref();
+
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_graph::<_, NodeId>(from, to, edge_mask, kwargs.stable_ids, kwargs.null_policy)
+        })
+    })
+}
+
+fn process_graph<C, T>(
+    from: &C,
+    to: &C,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64> + Send + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let num_threads = rayon::current_num_threads();
+    if from.num_rows() >= PARALLEL_THRESHOLD && num_threads > 1 {
+        return process_graph_parallel::<C, T>(
+            from,
+            to,
+            edge_mask,
+            stable_ids,
+            null_policy,
+            num_threads,
+        );
+    }
+
+    let graph = process_edges_with_filter::<C, T, _>(from, to, null_policy, |row| {
+        edge_mask.is_none_or(|mask| mask[row])
+    })?;
+
+    let group_ids = connected_components(graph.num_nodes(), &graph.edges);
+    let group_ids = resolve_group_ids(group_ids, &graph.node_to_id, stable_ids);
+
     // Map the group IDs to the original nodes
     let groups: Vec<u64> = from
-        .iter()
-        .map(|from_node| {
-            from_node
-                .and_then(|node| node_to_id.get(node))
-                .map(|&id| group_ids[id.as_usize()].into())
+        .keys()
+        .zip(to.keys())
+        .map(|(from_node, to_node)| {
+            null_policy
+                .row_nodes(from_node, to_node)
+                .0
+                .and_then(|node| graph.node_to_id.get(&node))
+                .map(|&id| group_ids[id.as_usize()])
                 .unwrap_or(0)
         })
         .collect();
 
     Ok(UInt64Chunked::from_vec("group".into(), groups).into_series())
 }
+
+fn process_graph_parallel<C, T>(
+    from: &C,
+    to: &C,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+    num_threads: usize,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64> + Send + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges_parallel::<C, T>(from, to, null_policy, edge_mask, num_threads)?;
+
+    let group_ids = connected_components_parallel(graph.num_nodes(), &graph.edges);
+    let group_ids = resolve_group_ids(group_ids, &graph.node_to_id, stable_ids);
+
+    // Map the group IDs to the original nodes, every thread handles a contiguous range of rows
+    let groups: Vec<Vec<u64>> = row_ranges(from.num_rows(), num_threads)
+        .into_par_iter()
+        .map(|(offset, len)| {
+            from.keys_in_range(offset, len)
+                .zip(to.keys_in_range(offset, len))
+                .map(|(from_node, to_node)| {
+                    null_policy
+                        .row_nodes(from_node, to_node)
+                        .0
+                        .and_then(|node| graph.node_to_id.get(&node))
+                        .map(|&id| group_ids[id.as_usize()])
+                        .unwrap_or(0)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(UInt64Chunked::from_vec("group".into(), groups.concat()).into_series())
+}
+
+fn graph_solver_nodes_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_dtype = node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]);
+
+    Ok(Field::new(
+        PlSmallStr::from("nodes"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("node"), node_dtype),
+            Field::new(PlSmallStr::from("group"), DataType::UInt64),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=graph_solver_nodes_output)]
+fn graph_solver_nodes(inputs: &[Series], kwargs: GraphSolverKwargs) -> PolarsResult<Series> {
+    let edge_mask = weight_mask(&inputs[0], inputs.get(2), &kwargs)?;
+    let edge_mask = edge_mask.as_deref();
+
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_graph_nodes::<_, NodeId>(
+                from,
+                to,
+                edge_mask,
+                kwargs.stable_ids,
+                kwargs.null_policy,
+            )
+        })
+    })
+}
+
+// One row per distinct node of both columns, in order of first appearance
+fn process_graph_nodes<C, T>(
+    from: &C,
+    to: &C,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges_with_filter::<C, T, _>(from, to, null_policy, |row| {
+        edge_mask.is_none_or(|mask| mask[row])
+    })?;
+
+    let group_ids = connected_components(graph.num_nodes(), &graph.edges);
+    let groups = resolve_group_ids(group_ids, &graph.node_to_id, stable_ids);
+
+    let fields = vec![
+        from.nodes_to_series(PlSmallStr::from("node"), &graph.nodes())?,
+        Series::new(PlSmallStr::from("group"), groups),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("nodes"), &fields).map(|ca| ca.into_series())
+}
+
+fn graph_solver_incremental_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_dtype = node_dtype(&[
+        input_fields[0].dtype(),
+        input_fields[1].dtype(),
+        input_fields[2].dtype(),
+    ]);
+
+    Ok(Field::new(
+        PlSmallStr::from("nodes"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("node"), node_dtype),
+            Field::new(PlSmallStr::from("group"), DataType::UInt64),
+            Field::new(PlSmallStr::from("previous_group"), DataType::UInt64),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=graph_solver_incremental_output)]
+fn graph_solver_incremental(inputs: &[Series], kwargs: GraphSolverKwargs) -> PolarsResult<Series> {
+    let state_group = to_uint64_chunked(&inputs[3])?;
+    let edge_mask = weight_mask(&inputs[0], inputs.get(4), &kwargs)?;
+    let edge_mask = edge_mask.as_deref();
+
+    polars_ensure!(
+        inputs[2].len() == state_group.len(),
+        ShapeMismatch: "state node and group columns differ in length: {} != {}",
+        inputs[2].len(),
+        state_group.len()
+    );
+
+    with_node_columns!(&[&inputs[0], &inputs[1], &inputs[2]], |columns| {
+        let (from, to, state_node) = (&columns[0], &columns[1], &columns[2]);
+        with_id_type!(
+            from.num_rows() + to.num_rows() + state_node.num_rows(),
+            |NodeId| {
+                process_incremental::<_, NodeId>(
+                    from,
+                    to,
+                    state_node,
+                    &state_group,
+                    edge_mask,
+                    kwargs.stable_ids,
+                    kwargs.null_policy,
+                )
+            }
+        )
+    })
+}
+
+// Reloads a previous node table and only unions the new edges on top of it. Nodes keep the group
+// they had, when groups merge the smallest previous id survives and new components get fresh ids
+fn process_incremental<C, T>(
+    from: &C,
+    to: &C,
+    state_node: &C,
+    state_group: &UInt64Chunked,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let mut node_to_id: NodeMap<C::Key<'_>, T> = NodeMap::default();
+    let mut id_counter: T = usize_to_t(0)?;
+
+    let state_ids = intern_nodes(state_node, &mut node_to_id, &mut id_counter)?;
+    let edges = extend_edges_with_filter(
+        from,
+        to,
+        null_policy,
+        |row| edge_mask.is_none_or(|mask| mask[row]),
+        &mut node_to_id,
+        &mut id_counter,
+    )?;
+    let num_nodes = id_counter.as_usize();
+
+    let mut uf = UnionFind::new(num_nodes);
+    let mut previous_groups: Vec<Option<u64>> = vec![None; num_nodes];
+
+    // Restore the previous components by linking every node to the first node of its group
+    let mut group_members: FxHashMap<u64, T> = FxHashMap::default();
+    for (id, group) in state_ids.iter().zip(state_group.iter()) {
+        if let (Some(id), Some(group)) = (*id, group) {
+            previous_groups[id.as_usize()].get_or_insert(group);
+            let member = *group_members.entry(group).or_insert(id);
+            uf.union(member, id);
+        }
+    }
+
+    // Only the delta edges have to be merged
+    edges.iter().for_each(|&(f_id, t_id)| {
+        uf.union(f_id, t_id);
+    });
+
+    let group_ids = dense_group_ids(&mut uf, num_nodes);
+
+    let groups = if stable_ids {
+        resolve_group_ids(group_ids, &node_to_id, true)
+    } else {
+        let num_groups = group_ids.iter().map(|g| g.as_usize()).max().unwrap_or(0);
+
+        // A merged component continues under the smallest of its previous ids
+        let mut surviving: Vec<Option<u64>> = vec![None; num_groups];
+        for (group, previous) in group_ids.iter().zip(previous_groups.iter()) {
+            if let Some(previous) = *previous {
+                let slot = &mut surviving[group.as_usize() - 1];
+                *slot = Some(slot.map_or(previous, |current| current.min(previous)));
+            }
+        }
+
+        // New components continue after the largest previous id, 0 stays reserved for null rows
+        let mut last_group = state_group.max().unwrap_or(0);
+        let group_values: Vec<u64> = surviving
+            .into_iter()
+            .map(|group| match group {
+                Some(group) => Ok(group),
+                None => {
+                    last_group = last_group.checked_add(1).ok_or_else(
+                        || polars_err!(ComputeError: "no group ids left after group {}", last_group),
+                    )?;
+                    Ok(last_group)
+                },
+            })
+            .collect::<PolarsResult<_>>()?;
+
+        group_ids
+            .iter()
+            .map(|group| group_values[group.as_usize() - 1])
+            .collect()
+    };
+
+    let fields = vec![
+        from.nodes_to_series(PlSmallStr::from("node"), &nodes_by_id(&node_to_id))?,
+        Series::new(PlSmallStr::from("group"), groups),
+        Series::new(PlSmallStr::from("previous_group"), previous_groups),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("nodes"), &fields).map(|ca| ca.into_series())
+}
+
+fn graph_component_lineage_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        PlSmallStr::from("lineage"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("old_group"), DataType::UInt64),
+            Field::new(PlSmallStr::from("new_group"), DataType::UInt64),
+            Field::new(PlSmallStr::from("num_nodes"), DataType::UInt64),
+            Field::new(PlSmallStr::from("is_merge"), DataType::Boolean),
+            Field::new(PlSmallStr::from("is_split"), DataType::Boolean),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=graph_component_lineage_output)]
+fn graph_component_lineage(inputs: &[Series], kwargs: GraphSolverKwargs) -> PolarsResult<Series> {
+    let state_group = to_uint64_chunked(&inputs[3])?;
+    let edge_mask = weight_mask(&inputs[0], inputs.get(4), &kwargs)?;
+    let edge_mask = edge_mask.as_deref();
+
+    polars_ensure!(
+        inputs[2].len() == state_group.len(),
+        ShapeMismatch: "state node and group columns differ in length: {} != {}",
+        inputs[2].len(),
+        state_group.len()
+    );
+
+    with_node_columns!(&[&inputs[0], &inputs[1], &inputs[2]], |columns| {
+        let (from, to, state_node) = (&columns[0], &columns[1], &columns[2]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_lineage::<_, NodeId>(
+                from,
+                to,
+                state_node,
+                &state_group,
+                edge_mask,
+                kwargs.stable_ids,
+                kwargs.null_policy,
+            )
+        })
+    })
+}
+
+// Recomputes the components of the current edges and counts how many nodes moved from every
+// previous group to every new group. Nodes without a previous group have a null old group,
+// nodes that no longer appear in any edge have a null new group.
+fn process_lineage<C, T>(
+    from: &C,
+    to: &C,
+    state_node: &C,
+    state_group: &UInt64Chunked,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges_with_filter::<C, T, _>(from, to, null_policy, |row| {
+        edge_mask.is_none_or(|mask| mask[row])
+    })?;
+    let num_nodes = graph.num_nodes();
+
+    let group_ids = connected_components(num_nodes, &graph.edges);
+    let new_groups = resolve_group_ids(group_ids, &graph.node_to_id, stable_ids);
+
+    let mut transitions: FxHashMap<(Option<u64>, Option<u64>), u64> = FxHashMap::default();
+    let mut seen_state_nodes: FxHashSet<C::Key<'_>> = FxHashSet::default();
+    let mut in_state = vec![false; num_nodes];
+
+    for (node, old_group) in state_node.keys().zip(state_group.iter()) {
+        let Some(node) = node else { continue };
+        let id = graph.node_to_id.get(&node).copied();
+        if !seen_state_nodes.insert(node) {
+            continue;
+        }
+
+        let new_group = id.map(|id| {
+            in_state[id.as_usize()] = true;
+            new_groups[id.as_usize()]
+        });
+        *transitions.entry((old_group, new_group)).or_insert(0) += 1;
+    }
+
+    for (id, _) in in_state.iter().enumerate().filter(|(_, &seen)| !seen) {
+        *transitions.entry((None, Some(new_groups[id]))).or_insert(0) += 1;
+    }
+
+    let mut transitions: Vec<_> = transitions.into_iter().collect();
+    transitions.sort_unstable_by_key(|&((old, new), _)| (old.is_none(), old, new.is_none(), new));
+
+    // A new group absorbing several old groups is a merge, an old group spread over several new
+    // groups is a split
+    let mut olds_per_new: FxHashMap<u64, u64> = FxHashMap::default();
+    let mut news_per_old: FxHashMap<u64, u64> = FxHashMap::default();
+    for &((old, new), _) in transitions.iter() {
+        if let (Some(old), Some(new)) = (old, new) {
+            *olds_per_new.entry(new).or_insert(0) += 1;
+            *news_per_old.entry(old).or_insert(0) += 1;
+        }
+    }
+
+    let mut old_column = Vec::with_capacity(transitions.len());
+    let mut new_column = Vec::with_capacity(transitions.len());
+    let mut num_nodes_column = Vec::with_capacity(transitions.len());
+    let mut is_merge = Vec::with_capacity(transitions.len());
+    let mut is_split = Vec::with_capacity(transitions.len());
+
+    for ((old, new), count) in transitions {
+        old_column.push(old);
+        new_column.push(new);
+        num_nodes_column.push(count);
+        is_merge.push(new.is_some_and(|new| olds_per_new.get(&new).is_some_and(|&n| n > 1)));
+        is_split.push(old.is_some_and(|old| news_per_old.get(&old).is_some_and(|&n| n > 1)));
+    }
+
+    let fields = vec![
+        Series::new(PlSmallStr::from("old_group"), old_column),
+        Series::new(PlSmallStr::from("new_group"), new_column),
+        Series::new(PlSmallStr::from("num_nodes"), num_nodes_column),
+        Series::new(PlSmallStr::from("is_merge"), is_merge),
+        Series::new(PlSmallStr::from("is_split"), is_split),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("lineage"), &fields).map(|ca| ca.into_series())
+}
+
+fn graph_components_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        PlSmallStr::from("components"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("weak_component"), DataType::UInt64),
+            Field::new(PlSmallStr::from("strong_component"), DataType::UInt64),
+            Field::new(PlSmallStr::from("component_size"), DataType::UInt64),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=graph_components_output)]
+fn graph_components(inputs: &[Series], kwargs: ComponentsKwargs) -> PolarsResult<Series> {
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_components::<_, NodeId>(from, to, kwargs.null_policy)
+        })
+    })
+}
+
+// Weak and strong components share a single pass over the node keys
+fn process_components<C, T>(from: &C, to: &C, null_policy: NullPolicy) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges::<C, T>(from, to, null_policy)?;
+    let num_nodes = graph.num_nodes();
+
+    let mut uf = union_edges(num_nodes, &graph.edges);
+    let weak_ids = dense_group_ids(&mut uf, num_nodes);
+    let strong_ids = number_components(&tarjan_scc(&graph.adjacency()));
+
+    let mut weak_components = Vec::with_capacity(from.num_rows());
+    let mut strong_components = Vec::with_capacity(from.num_rows());
+    let mut component_sizes = Vec::with_capacity(from.num_rows());
+
+    for (from_node, to_node) in from.keys().zip(to.keys()) {
+        let (from_node, _) = null_policy.row_nodes(from_node, to_node);
+        match from_node.and_then(|node| graph.node_to_id.get(&node)) {
+            Some(&id) => {
+                let weak = weak_ids[id.as_usize()];
+                weak_components.push(weak.into());
+                strong_components.push(strong_ids[id.as_usize()]);
+                component_sizes.push(uf.component_size(id) as u64);
+            },
+            None => {
+                weak_components.push(0);
+                strong_components.push(0);
+                component_sizes.push(0);
+            },
+        }
+    }
+
+    let fields = vec![
+        Series::new(PlSmallStr::from("weak_component"), weak_components),
+        Series::new(PlSmallStr::from("strong_component"), strong_components),
+        Series::new(PlSmallStr::from("component_size"), component_sizes),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("components"), &fields).map(|ca| ca.into_series())
+}
+
+fn component_summary_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_dtype = node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]);
+
+    Ok(Field::new(
+        PlSmallStr::from("component_summary"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("group"), DataType::UInt64),
+            Field::new(PlSmallStr::from("num_nodes"), DataType::UInt64),
+            Field::new(PlSmallStr::from("num_edges"), DataType::UInt64),
+            Field::new(PlSmallStr::from("density"), DataType::Float64),
+            Field::new(PlSmallStr::from("representative"), node_dtype),
+            Field::new(PlSmallStr::from("is_tree"), DataType::Boolean),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=component_summary_output)]
+fn graph_component_summary(inputs: &[Series], kwargs: GraphSolverKwargs) -> PolarsResult<Series> {
+    let edge_mask = weight_mask(&inputs[0], inputs.get(2), &kwargs)?;
+    let edge_mask = edge_mask.as_deref();
+
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_summary::<_, NodeId>(from, to, edge_mask, kwargs.stable_ids, kwargs.null_policy)
+        })
+    })
+}
+
+fn process_summary<C, T>(
+    from: &C,
+    to: &C,
+    edge_mask: Option<&[bool]>,
+    stable_ids: bool,
+    null_policy: NullPolicy,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges_with_filter::<C, T, _>(from, to, null_policy, |row| {
+        edge_mask.is_none_or(|mask| mask[row])
+    })?;
+    let num_nodes = graph.num_nodes();
+
+    let mut uf = union_edges(num_nodes, &graph.edges);
+    let group_ids = dense_group_ids(&mut uf, num_nodes);
+    let num_groups = group_ids.iter().map(|g| g.as_usize()).max().unwrap_or(0);
+
+    let id_to_node = graph.nodes();
+
+    // Groups are numbered in order of their first node, so the first node seen
+    // for a group is also its smallest id and becomes the representative
+    let mut node_counts = Vec::with_capacity(num_groups);
+    let mut representatives = Vec::with_capacity(num_groups);
+    for (id, group) in group_ids.iter().enumerate() {
+        if group.as_usize() > node_counts.len() {
+            representatives.push(id_to_node[id]);
+            node_counts.push(uf.component_size(bounded_id(id)) as u64);
+        }
+    }
+
+    let mut edge_counts = vec![0u64; num_groups];
+    for &(from_id, _) in graph.edges.iter() {
+        edge_counts[group_ids[from_id.as_usize()].as_usize() - 1] += 1;
+    }
+
+    // Stable ids are derived from the smallest node, which then also serves as representative
+    let groups: Vec<u64> = if stable_ids {
+        let node_keys: Vec<Cow<str>> = id_to_node.iter().map(|node| node.key_str()).collect();
+        let min_nodes = min_node_per_group(&group_ids, &node_keys);
+        representatives = min_nodes.iter().map(|&id| id_to_node[id]).collect();
+        min_nodes
+            .iter()
+            .map(|&id| stable_hash(&node_keys[id]))
+            .collect()
+    } else {
+        (1..=num_groups as u64).collect()
+    };
+    let density: Vec<f64> = node_counts
+        .iter()
+        .zip(edge_counts.iter())
+        .map(|(&nodes, &edges)| {
+            if nodes > 1 {
+                2.0 * edges as f64 / (nodes as f64 * (nodes - 1) as f64)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let is_tree: Vec<bool> = node_counts
+        .iter()
+        .zip(edge_counts.iter())
+        .map(|(&nodes, &edges)| edges + 1 == nodes)
+        .collect();
+
+    let fields = vec![
+        Series::new(PlSmallStr::from("group"), groups),
+        Series::new(PlSmallStr::from("num_nodes"), node_counts),
+        Series::new(PlSmallStr::from("num_edges"), edge_counts),
+        Series::new(PlSmallStr::from("density"), density),
+        from.nodes_to_series(PlSmallStr::from("representative"), &representatives)?,
+        Series::new(PlSmallStr::from("is_tree"), is_tree),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("component_summary"), &fields)
+        .map(|ca| ca.into_series())
+}
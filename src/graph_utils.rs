@@ -1,12 +1,20 @@
+use polars::export::arrow::array::Array;
 use polars::prelude::*;
+use rayon::prelude::*;
 use rustc_hash::FxHashMap;
+use serde::Deserialize;
 use smallvec::SmallVec;
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
 use std::convert::TryFrom;
+use std::hash::Hash;
 
 // Type aliases to simplify complex types
-type NodeMap<T> = FxHashMap<String, T>;
-type EdgeList<T> = SmallVec<[(T, T); 1024]>;
-type ProcessResult<T> = PolarsResult<(NodeMap<T>, T, EdgeList<T>)>;
+pub type NodeMap<K, T> = FxHashMap<K, T>;
+pub type EdgeList<T> = SmallVec<[(T, T); 1024]>;
+type GraphResult<K, T> = PolarsResult<Graph<K, T>>;
+// Nodes of a range of rows in order of first appearance, and the edges between their local ids
+type LocalEdges<K> = (Vec<K>, Vec<(usize, usize)>);
 
 // Rest of the traits and implementations remain the same...
 pub trait AsUsize {
@@ -31,12 +39,28 @@ impl AsUsize for u64 {
     }
 }
 
-pub fn usize_to_t<T>(value: usize) -> T
+/// Converts a node count to a node id, failing when the graph has more nodes than `T` can number.
+pub fn usize_to_t<T>(value: usize) -> PolarsResult<T>
+where
+    T: TryFrom<usize>,
+{
+    T::try_from(value).map_err(|_| {
+        polars_err!(
+            ComputeError: "graph has at least {} nodes, more than {} node ids can number",
+            value,
+            std::any::type_name::<T>()
+        )
+    })
+}
+
+// Converts a value bounded by the number of interned nodes. Interning checks that the node count
+// fits in `T`, so unlike `usize_to_t` this can't fail.
+pub fn bounded_id<T>(value: usize) -> T
 where
     T: TryFrom<usize>,
-    <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    T::try_from(value).expect("Invalid conversion from usize")
+    T::try_from(value)
+        .unwrap_or_else(|_| unreachable!("id {} exceeds the number of interned nodes", value))
 }
 
 pub fn to_string_chunked(series: &Series) -> PolarsResult<StringChunked> {
@@ -55,6 +79,14 @@ pub fn to_int64_chunked(series: &Series) -> PolarsResult<Int64Chunked> {
     }
 }
 
+pub fn to_uint64_chunked(series: &Series) -> PolarsResult<UInt64Chunked> {
+    if series.dtype() == &DataType::UInt64 {
+        Ok(series.u64()?.clone())
+    } else {
+        Ok(series.cast(&DataType::UInt64)?.u64()?.clone())
+    }
+}
+
 pub fn to_float64_chunked(series: &Series) -> PolarsResult<Float64Chunked> {
     if series.dtype() == &DataType::Float64 {
         Ok(series.f64()?.clone())
@@ -63,38 +95,808 @@ pub fn to_float64_chunked(series: &Series) -> PolarsResult<Float64Chunked> {
     }
 }
 
-fn get_or_insert_id<T>(node: &str, node_to_id: &mut NodeMap<T>, id_counter: &mut T) -> T
+// 64-bit FNV-1a, unlike the hashers in std and rustc-hash its output is fixed across
+// versions and platforms, so it can be used for ids that are persisted
+pub fn stable_hash(key: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    key.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// A node key that can be interned by value. Keys order by value, so integer nodes sort
+/// numerically.
+pub trait NodeKey: Hash + Ord + Clone + Send + Sync {
+    /// The key as it would appear after casting the node column to String.
+    fn key_str(&self) -> Cow<'_, str>;
+}
+
+impl NodeKey for &str {
+    fn key_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl NodeKey for i64 {
+    fn key_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl NodeKey for u64 {
+    fn key_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+/// A column of nodes, as passed in the `from` and `to` inputs of the expressions. Keys may borrow
+/// from the column, so interning strings doesn't copy them.
+pub trait NodeColumn: Sync {
+    type Key<'a>: NodeKey
+    where
+        Self: 'a;
+
+    fn num_rows(&self) -> usize;
+
+    /// The key of every row, `None` for null rows.
+    fn keys(&self) -> impl Iterator<Item = Option<Self::Key<'_>>> + '_;
+
+    /// The keys of the rows in `offset..offset + len`.
+    fn keys_in_range(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> impl Iterator<Item = Option<Self::Key<'_>>> + '_;
+
+    /// The rows in `offset..offset + len` as a column of their own, without copying.
+    fn slice_rows(&self, offset: usize, len: usize) -> Self
+    where
+        Self: Sized;
+
+    /// Builds a column of nodes with the dtype of the input columns.
+    fn nodes_to_series(&self, name: PlSmallStr, nodes: &[&Self::Key<'_>]) -> PolarsResult<Series>;
+}
+
+// The chunks overlapping `offset..offset + len`, each with the range of its own indices to visit
+fn chunk_ranges<'a, A: Array>(
+    chunks: impl Iterator<Item = &'a A>,
+    offset: usize,
+    len: usize,
+) -> impl Iterator<Item = (&'a A, std::ops::Range<usize>)> {
+    let end = offset + len;
+    let mut chunk_start = 0;
+
+    chunks.filter_map(move |chunk| {
+        let start = chunk_start;
+        chunk_start += chunk.len();
+        let from = offset.max(start);
+        let to = end.min(chunk_start);
+        (from < to).then(|| (chunk, from - start..to - start))
+    })
+}
+
+impl NodeColumn for StringChunked {
+    type Key<'a> = &'a str;
+
+    fn num_rows(&self) -> usize {
+        self.len()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+        self.iter()
+    }
+
+    fn keys_in_range(&self, offset: usize, len: usize) -> impl Iterator<Item = Option<&str>> + '_ {
+        chunk_ranges(self.downcast_iter(), offset, len).flat_map(|(chunk, range)| {
+            range.map(move |i| chunk.is_valid(i).then(|| chunk.value(i)))
+        })
+    }
+
+    fn slice_rows(&self, offset: usize, len: usize) -> Self {
+        self.slice(offset as i64, len)
+    }
+
+    fn nodes_to_series(&self, name: PlSmallStr, nodes: &[&&str]) -> PolarsResult<Series> {
+        let nodes: Vec<&str> = nodes.iter().map(|&&node| node).collect();
+        Ok(Series::new(name, nodes))
+    }
+}
+
+/// Integer nodes are interned by value, `dtype` is the dtype the nodes are returned in.
+pub struct IntegerNodes<P: PolarsNumericType> {
+    values: ChunkedArray<P>,
+    dtype: DataType,
+}
+
+impl<P> NodeColumn for IntegerNodes<P>
+where
+    P: PolarsNumericType,
+    P::Native: NodeKey,
+    ChunkedArray<P>: IntoSeries,
+{
+    type Key<'a> = P::Native;
+
+    fn num_rows(&self) -> usize {
+        self.values.len()
+    }
+
+    fn keys(&self) -> impl Iterator<Item = Option<P::Native>> + '_ {
+        self.values.iter()
+    }
+
+    fn keys_in_range(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> impl Iterator<Item = Option<P::Native>> + '_ {
+        chunk_ranges(self.values.downcast_iter(), offset, len).flat_map(|(chunk, range)| {
+            range.map(move |i| chunk.is_valid(i).then(|| chunk.value(i)))
+        })
+    }
+
+    fn slice_rows(&self, offset: usize, len: usize) -> Self {
+        IntegerNodes {
+            values: self.values.slice(offset as i64, len),
+            dtype: self.dtype.clone(),
+        }
+    }
+
+    fn nodes_to_series(&self, name: PlSmallStr, nodes: &[&P::Native]) -> PolarsResult<Series> {
+        let nodes: Vec<P::Native> = nodes.iter().map(|&&node| node).collect();
+        ChunkedArray::<P>::from_vec(name, nodes)
+            .into_series()
+            .cast(&self.dtype)
+    }
+}
+
+/// Node columns converted to a shared key type, see [`with_node_columns`].
+pub enum NodeColumns {
+    String(Vec<StringChunked>),
+    Int64(Vec<IntegerNodes<Int64Type>>),
+    UInt64(Vec<IntegerNodes<UInt64Type>>),
+}
+
+fn integer_bits(dtype: &DataType) -> u8 {
+    match dtype {
+        DataType::Int8 | DataType::UInt8 => 8,
+        DataType::Int16 | DataType::UInt16 => 16,
+        DataType::Int32 | DataType::UInt32 => 32,
+        _ => 64,
+    }
+}
+
+/// The dtype nodes are returned in. Integer columns of the same signedness keep the widest of
+/// their dtypes, any other combination of columns is cast to String.
+pub fn node_dtype(dtypes: &[&DataType]) -> DataType {
+    let all_signed = dtypes.iter().all(|dtype| dtype.is_signed_integer());
+    let all_unsigned = dtypes.iter().all(|dtype| dtype.is_unsigned_integer());
+
+    if all_signed || all_unsigned {
+        dtypes
+            .iter()
+            .max_by_key(|dtype| integer_bits(dtype))
+            .map_or(DataType::String, |&dtype| dtype.clone())
+    } else {
+        DataType::String
+    }
+}
+
+// Categorical columns have no fast path yet: the crate builds without polars' dtype-categorical
+// feature, because pyo3-polars 0.17 doesn't compile with it against polars 0.43, and without it
+// dictionary arrays can't be imported into the plugin at all.
+pub fn node_columns(columns: &[&Series]) -> PolarsResult<NodeColumns> {
+    let dtypes: Vec<&DataType> = columns.iter().map(|column| column.dtype()).collect();
+    let dtype = node_dtype(&dtypes);
+
+    if dtype.is_signed_integer() {
+        let columns = columns
+            .iter()
+            .map(|column| {
+                Ok(IntegerNodes {
+                    values: to_int64_chunked(column)?,
+                    dtype: dtype.clone(),
+                })
+            })
+            .collect::<PolarsResult<_>>()?;
+        Ok(NodeColumns::Int64(columns))
+    } else if dtype.is_unsigned_integer() {
+        let columns = columns
+            .iter()
+            .map(|column| {
+                Ok(IntegerNodes {
+                    values: to_uint64_chunked(column)?,
+                    dtype: dtype.clone(),
+                })
+            })
+            .collect::<PolarsResult<_>>()?;
+        Ok(NodeColumns::UInt64(columns))
+    } else {
+        let columns = columns
+            .iter()
+            .map(|column| to_string_chunked(column))
+            .collect::<PolarsResult<_>>()?;
+        Ok(NodeColumns::String(columns))
+    }
+}
+
+/// Converts the node series to a shared key type and evaluates `$body` with `$columns` bound to
+/// the converted columns, in the same order as the series.
+macro_rules! with_node_columns {
+    ($series:expr, |$columns:ident| $body:expr) => {
+        match $crate::graph_utils::node_columns($series)? {
+            $crate::graph_utils::NodeColumns::String($columns) => $body,
+            $crate::graph_utils::NodeColumns::Int64($columns) => $body,
+            $crate::graph_utils::NodeColumns::UInt64($columns) => $body,
+        }
+    };
+}
+pub(crate) use with_node_columns;
+
+/// Evaluates `$body` with `$id` aliased to the narrowest node id type that can number
+/// `$max_nodes` nodes. Every expression picks its id width through this macro, with the total
+/// number of rows of its node columns as upper bound.
+macro_rules! with_id_type {
+    ($max_nodes:expr, |$id:ident| $body:expr) => {{
+        let max_nodes: usize = $max_nodes;
+        if max_nodes <= u16::MAX as usize {
+            type $id = u16;
+            $body
+        } else if max_nodes <= u32::MAX as usize {
+            type $id = u32;
+            $body
+        } else {
+            type $id = u64;
+            $body
+        }
+    }};
+}
+pub(crate) use with_id_type;
+
+/// How rows with a null `from` or `to` node are handled, shared by the kwargs of every expression.
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NullPolicy {
+    /// The row doesn't contribute an edge.
+    Drop,
+    /// The first row with a null node raises an error.
+    Error,
+    /// A row with a single null node adds its other node, without an edge.
+    Isolate,
+}
+
+impl NullPolicy {
+    /// The nodes of a row, an isolated row only has its non-null node as `from`.
+    pub fn row_nodes<K>(self, from: Option<K>, to: Option<K>) -> (Option<K>, Option<K>) {
+        match (self, from, to) {
+            (NullPolicy::Isolate, None, Some(node)) => (Some(node), None),
+            (_, from, to) => (from, to),
+        }
+    }
+
+    /// The node of a row and the other end of its edge, `None` when the row is dropped. An
+    /// isolated row has no edge, so only its node is returned.
+    pub fn edge<K>(
+        self,
+        row: usize,
+        from: Option<K>,
+        to: Option<K>,
+    ) -> PolarsResult<Option<(K, Option<K>)>> {
+        if self == NullPolicy::Error && (from.is_none() || to.is_none()) {
+            let column = if from.is_none() { "from" } else { "to" };
+            polars_bail!(
+                ComputeError: "row {} has a null '{}' node, use null_policy 'drop' or 'isolate' to allow nulls",
+                row, column
+            );
+        }
+
+        match self.row_nodes(from, to) {
+            (Some(from), Some(to)) => Ok(Some((from, Some(to)))),
+            (Some(node), None) if self == NullPolicy::Isolate => Ok(Some((node, None))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Whether edges from a node to itself are part of the graph, shared by the kwargs of the
+/// expressions affected by self-loops.
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfLoops {
+    Keep,
+    /// Self-loops are removed, their node stays in the graph.
+    Drop,
+}
+
+/// The interned nodes and the edges between them, every expression builds one from its inputs.
+pub struct Graph<K, T> {
+    pub node_to_id: NodeMap<K, T>,
+    pub edges: EdgeList<T>,
+    /// Weight of every edge, in the same order as `edges`.
+    pub weights: Option<Vec<f64>>,
+    /// Undirected graphs traverse every edge in both directions.
+    pub directed: bool,
+}
+
+impl<K, T> Graph<K, T>
+where
+    T: TryFrom<usize> + Copy + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    fn new(node_to_id: NodeMap<K, T>, edges: EdgeList<T>) -> Self {
+        Graph {
+            node_to_id,
+            edges,
+            weights: None,
+            directed: true,
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.node_to_id.len()
+    }
+
+    /// Removes self-loops and repeated edges between the same pair of nodes, keeping the first
+    /// edge of every pair in row order with the smallest weight of the pair. Undirected graphs
+    /// treat both orientations of an edge as the same pair, so `directed` has to be set
+    /// beforehand. Nodes stay in the graph when all their edges are removed.
+    pub fn simplify(&mut self, dedupe_edges: bool, self_loops: SelfLoops) {
+        if !dedupe_edges && self_loops == SelfLoops::Keep {
+            return;
+        }
+
+        // The row of the first edge of every pair
+        let mut first_rows = FxHashMap::default();
+        let mut keep = vec![false; self.edges.len()];
+        for (row, &(from_id, to_id)) in self.edges.iter().enumerate() {
+            let (from_id, to_id) = (from_id.as_usize(), to_id.as_usize());
+            if from_id == to_id && self_loops == SelfLoops::Drop {
+                continue;
+            }
+            if !dedupe_edges {
+                keep[row] = true;
+                continue;
+            }
+            let pair = if self.directed || from_id <= to_id {
+                (from_id, to_id)
+            } else {
+                (to_id, from_id)
+            };
+            match first_rows.entry(pair) {
+                Entry::Occupied(entry) => {
+                    if let Some(weights) = &mut self.weights {
+                        let first: usize = *entry.get();
+                        weights[first] = weights[first].min(weights[row]);
+                    }
+                },
+                Entry::Vacant(entry) => {
+                    entry.insert(row);
+                    keep[row] = true;
+                },
+            }
+        }
+
+        self.edges = keep_marked(&self.edges, &keep);
+        if let Some(weights) = &mut self.weights {
+            *weights = keep_marked(weights, &keep);
+        }
+    }
+
+    /// Node keys indexed by node id.
+    pub fn nodes(&self) -> Vec<&K> {
+        nodes_by_id(&self.node_to_id)
+    }
+
+    /// The outgoing edges of every node.
+    pub fn adjacency(&self) -> Csr<T> {
+        Csr::new(
+            self.num_nodes(),
+            &self.edges,
+            self.weights.as_deref(),
+            self.directed,
+        )
+    }
+
+    /// The incoming edges of every node, the same as [`Graph::adjacency`] for undirected graphs.
+    pub fn incoming_adjacency(&self) -> Csr<T> {
+        if !self.directed {
+            return self.adjacency();
+        }
+        Csr::from_arcs(
+            self.num_nodes(),
+            || {
+                self.edges
+                    .iter()
+                    .enumerate()
+                    .map(|(edge, &(from_id, to_id))| (to_id, from_id, edge))
+            },
+            self.weights.as_deref(),
+        )
+    }
+}
+
+/// Compressed sparse row adjacency, the neighbours of node `i` are stored in
+/// `targets[offsets[i]..offsets[i + 1]]` in the order of the edges.
+pub struct Csr<T> {
+    offsets: Vec<usize>,
+    targets: Vec<T>,
+    weights: Option<Vec<f64>>,
+}
+
+impl<T> Csr<T>
+where
+    T: TryFrom<usize> + Copy + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    pub fn new(
+        num_nodes: usize,
+        edges: &[(T, T)],
+        weights: Option<&[f64]>,
+        directed: bool,
+    ) -> Self {
+        Csr::from_arcs(
+            num_nodes,
+            || {
+                edges
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(edge, &(from_id, to_id))| {
+                        let reverse = (!directed).then_some((to_id, from_id, edge));
+                        std::iter::once((from_id, to_id, edge)).chain(reverse)
+                    })
+            },
+            weights,
+        )
+    }
+
+    // Builds the adjacency from `(from, to, edge)` arcs, where `edge` indexes `weights`. The arcs
+    // are iterated twice, once to count the neighbours of every node and once to place them.
+    fn from_arcs<I>(num_nodes: usize, arcs: impl Fn() -> I, weights: Option<&[f64]>) -> Self
+    where
+        I: Iterator<Item = (T, T, usize)>,
+    {
+        let mut offsets = vec![0; num_nodes + 1];
+        for (from_id, _, _) in arcs() {
+            offsets[from_id.as_usize() + 1] += 1;
+        }
+        for node in 0..num_nodes {
+            offsets[node + 1] += offsets[node];
+        }
+
+        let num_targets = offsets[num_nodes];
+        let mut targets = vec![bounded_id(0); num_targets];
+        let mut target_weights = weights.map(|_| vec![0.0; num_targets]);
+        let mut next = offsets[..num_nodes].to_vec();
+
+        for (from_id, to_id, edge) in arcs() {
+            let slot = &mut next[from_id.as_usize()];
+            targets[*slot] = to_id;
+            if let (Some(target_weights), Some(weights)) = (target_weights.as_mut(), weights) {
+                target_weights[*slot] = weights[edge];
+            }
+            *slot += 1;
+        }
+
+        Csr {
+            offsets,
+            targets,
+            weights: target_weights,
+        }
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn neighbors(&self, node: usize) -> &[T] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// The weights of the edges returned by [`Csr::neighbors`], empty for unweighted graphs.
+    pub fn neighbor_weights(&self, node: usize) -> &[f64] {
+        self.weights.as_ref().map_or(&[], |weights| {
+            &weights[self.offsets[node]..self.offsets[node + 1]]
+        })
+    }
+}
+
+/// Scales the scores to sum to 1, they stay 0 when every score is 0.
+pub(crate) fn scale_to_sum(scores: &mut [f64]) {
+    let total: f64 = scores.iter().sum();
+    if total > 0.0 {
+        scores.iter_mut().for_each(|score| *score /= total);
+    }
+}
+
+// Reverse lookup of node keys, indexed by node id
+pub fn nodes_by_id<K, T>(node_to_id: &NodeMap<K, T>) -> Vec<&K>
+where
+    T: Copy + AsUsize,
+{
+    let mut nodes = vec![None; node_to_id.len()];
+    for (node, &id) in node_to_id.iter() {
+        nodes[id.as_usize()] = Some(node);
+    }
+    nodes.into_iter().flatten().collect()
+}
+
+// The values whose entry in `keep` is set, in order
+fn keep_marked<V, C>(values: &[V], keep: &[bool]) -> C
+where
+    V: Copy,
+    C: FromIterator<V>,
+{
+    values
+        .iter()
+        .zip(keep)
+        .filter(|(_, &keep)| keep)
+        .map(|(&value, _)| value)
+        .collect()
+}
+
+// The id counter always holds the number of nodes, so every id and count fits in `T`
+fn get_or_insert_id<K, T>(
+    node: K,
+    node_to_id: &mut NodeMap<K, T>,
+    id_counter: &mut T,
+) -> PolarsResult<T>
 where
+    K: NodeKey,
     T: TryFrom<usize> + Copy + PartialEq + AsUsize,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    *node_to_id.entry(node.to_string()).or_insert_with(|| {
-        let id = *id_counter;
-        *id_counter = usize_to_t(id_counter.as_usize() + 1);
-        id
-    })
+    match node_to_id.entry(node) {
+        Entry::Occupied(entry) => Ok(*entry.get()),
+        Entry::Vacant(entry) => {
+            let id = *id_counter;
+            *id_counter = usize_to_t(id_counter.as_usize() + 1)?;
+            Ok(*entry.insert(id))
+        },
+    }
+}
+
+pub fn process_edges<'a, C, T>(
+    from: &'a C,
+    to: &'a C,
+    null_policy: NullPolicy,
+) -> GraphResult<C::Key<'a>, T>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    process_edges_with_filter(from, to, null_policy, |_| true)
+}
+
+/// Like [`process_edges`], but only keeps the edges of rows for which `keep` returns `true`.
+/// Nodes of rejected rows are still assigned an id, so they end up as isolated nodes.
+pub fn process_edges_with_filter<'a, C, T, F>(
+    from: &'a C,
+    to: &'a C,
+    null_policy: NullPolicy,
+    keep: F,
+) -> GraphResult<C::Key<'a>, T>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+    F: FnMut(usize) -> bool,
+{
+    let mut node_to_id: NodeMap<C::Key<'a>, T> = FxHashMap::default();
+    let mut id_counter: T = usize_to_t(0)?;
+    let edges = extend_edges_with_filter(
+        from,
+        to,
+        null_policy,
+        keep,
+        &mut node_to_id,
+        &mut id_counter,
+    )?;
+
+    Ok(Graph::new(node_to_id, edges))
+}
+
+/// Like [`process_edges`], with the weight of every edge. Rows with a null weight don't
+/// contribute an edge, their nodes are still assigned an id.
+pub fn process_weighted_edges<'a, C, T>(
+    from: &'a C,
+    to: &'a C,
+    weights: &Float64Chunked,
+    null_policy: NullPolicy,
+) -> GraphResult<C::Key<'a>, T>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let row_weights: Vec<Option<f64>> = weights.iter().collect();
+    let mut edge_weights = Vec::with_capacity(row_weights.len());
+
+    // The filter is only consulted for rows with both nodes, in row order
+    let mut graph = process_edges_with_filter(from, to, null_policy, |row| {
+        row_weights[row]
+            .map(|weight| edge_weights.push(weight))
+            .is_some()
+    })?;
+    graph.weights = Some(edge_weights);
+
+    Ok(graph)
+}
+
+/// How the rows of the edge columns become a graph, shared by the ranking and path expressions.
+#[derive(Clone, Copy)]
+pub(crate) struct IngestOptions {
+    pub(crate) null_policy: NullPolicy,
+    pub(crate) dedupe_edges: bool,
+    pub(crate) self_loops: SelfLoops,
+    pub(crate) directed: bool,
+}
+
+/// Processes the edges with their optional weights, then removes the repeated edges and
+/// self-loops that `options` asks for. Rows with a null weight don't contribute an edge, their
+/// nodes are still part of the graph.
+pub(crate) fn build_graph<'a, C, T>(
+    from: &'a C,
+    to: &'a C,
+    weights: Option<&Float64Chunked>,
+    options: IngestOptions,
+) -> GraphResult<C::Key<'a>, T>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let mut graph = match weights {
+        Some(weights) => process_weighted_edges::<C, T>(from, to, weights, options.null_policy)?,
+        None => process_edges::<C, T>(from, to, options.null_policy)?,
+    };
+    graph.directed = options.directed;
+    graph.simplify(options.dedupe_edges, options.self_loops);
+
+    Ok(graph)
 }
 
-pub fn process_edges<T>(from: &StringChunked, to: &StringChunked) -> ProcessResult<T>
+/// Processes the edges on top of an existing node mapping, new nodes continue from `id_counter`.
+pub fn extend_edges_with_filter<'a, C, T, F>(
+    from: &'a C,
+    to: &'a C,
+    null_policy: NullPolicy,
+    mut keep: F,
+    node_to_id: &mut NodeMap<C::Key<'a>, T>,
+    id_counter: &mut T,
+) -> PolarsResult<EdgeList<T>>
 where
+    C: NodeColumn,
     T: TryFrom<usize> + Copy + PartialEq + AsUsize,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
+    F: FnMut(usize) -> bool,
 {
-    let mut node_to_id: NodeMap<T> = FxHashMap::default();
-    let mut id_counter: T = usize_to_t(0);
-    let mut edges = EdgeList::with_capacity(from.len());
+    let mut edges = EdgeList::with_capacity(from.num_rows());
 
     // Process the edges
-    from.iter()
-        .zip(to.iter())
-        .try_for_each(|(from_node, to_node)| -> PolarsResult<()> {
-            if let (Some(f), Some(t)) = (from_node, to_node) {
-                let f_id = get_or_insert_id(f, &mut node_to_id, &mut id_counter);
-                let t_id = get_or_insert_id(t, &mut node_to_id, &mut id_counter);
-                edges.push((f_id, t_id));
+    from.keys().zip(to.keys()).enumerate().try_for_each(
+        |(row, (from_node, to_node))| -> PolarsResult<()> {
+            if let Some((f, t)) = null_policy.edge(row, from_node, to_node)? {
+                let f_id = get_or_insert_id(f, node_to_id, id_counter)?;
+                if let Some(t) = t {
+                    let t_id = get_or_insert_id(t, node_to_id, id_counter)?;
+                    if keep(row) {
+                        edges.push((f_id, t_id));
+                    }
+                }
             }
             Ok(())
-        })?;
+        },
+    )?;
 
-    Ok((node_to_id, id_counter, edges))
+    Ok(edges)
+}
+
+/// Splits `len` rows into at most `num_ranges` contiguous `(offset, length)` ranges.
+pub fn row_ranges(len: usize, num_ranges: usize) -> Vec<(usize, usize)> {
+    let range_len = len.div_ceil(num_ranges.max(1)).max(1);
+    (0..len)
+        .step_by(range_len)
+        .map(|offset| (offset, range_len.min(len - offset)))
+        .collect()
+}
+
+/// Parallel version of [`process_edges_with_filter`], only the edges of rows where `edge_mask` is
+/// `true` are kept. The rows are split in contiguous ranges that are interned on separate threads,
+/// the local ids are then merged in range order so every node gets the same id as it would in the
+/// sequential version.
+pub fn process_edges_parallel<'a, C, T>(
+    from: &'a C,
+    to: &'a C,
+    null_policy: NullPolicy,
+    edge_mask: Option<&[bool]>,
+    num_threads: usize,
+) -> GraphResult<C::Key<'a>, T>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Send + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    // Intern every range on its own, nodes are listed in order of first appearance
+    let local: Vec<LocalEdges<C::Key<'a>>> = row_ranges(from.num_rows(), num_threads)
+        .into_par_iter()
+        .map(|(offset, len)| {
+            let mut local_ids: FxHashMap<C::Key<'a>, usize> = FxHashMap::default();
+            let mut nodes = Vec::new();
+            let mut edges = Vec::with_capacity(len);
+
+            let rows = from
+                .keys_in_range(offset, len)
+                .zip(to.keys_in_range(offset, len));
+            for (row, (from_node, to_node)) in rows.enumerate() {
+                if let Some((f, t)) = null_policy.edge(offset + row, from_node, to_node)? {
+                    let mut intern = |node: C::Key<'a>| {
+                        *local_ids.entry(node).or_insert_with_key(|node| {
+                            nodes.push(node.clone());
+                            nodes.len() - 1
+                        })
+                    };
+                    let f_id = intern(f);
+                    if let Some(t) = t {
+                        let t_id = intern(t);
+                        if edge_mask.is_none_or(|mask| mask[offset + row]) {
+                            edges.push((f_id, t_id));
+                        }
+                    }
+                }
+            }
+
+            Ok((nodes, edges))
+        })
+        // Every range finishes before the errors are checked, so the first failing range in row
+        // order reports the first null row, whichever thread fails first
+        .collect::<Vec<PolarsResult<_>>>()
+        .into_iter()
+        .collect::<PolarsResult<_>>()?;
+    let (local_nodes, local_edges): (Vec<_>, Vec<_>) = local.into_iter().unzip();
+
+    // Merge the local ids in range order, which preserves the global order of first appearance
+    let mut node_to_id: NodeMap<C::Key<'a>, T> = FxHashMap::default();
+    let mut id_counter: T = usize_to_t(0)?;
+    let global_ids: Vec<Vec<T>> = local_nodes
+        .into_iter()
+        .map(|nodes| {
+            nodes
+                .into_iter()
+                .map(|node| get_or_insert_id(node, &mut node_to_id, &mut id_counter))
+                .collect()
+        })
+        .collect::<PolarsResult<_>>()?;
+
+    let edges: Vec<Vec<(T, T)>> = local_edges
+        .par_iter()
+        .zip(global_ids.par_iter())
+        .map(|(edges, ids)| edges.iter().map(|&(f, t)| (ids[f], ids[t])).collect())
+        .collect();
+
+    Ok(Graph::new(
+        node_to_id,
+        edges.into_iter().flatten().collect(),
+    ))
+}
+
+/// Assigns ids to a column of nodes, returning the id of every row (`None` for null rows).
+pub fn intern_nodes<'a, C, T>(
+    nodes: &'a C,
+    node_to_id: &mut NodeMap<C::Key<'a>, T>,
+    id_counter: &mut T,
+) -> PolarsResult<Vec<Option<T>>>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    nodes
+        .keys()
+        .map(|node| {
+            node.map(|n| get_or_insert_id(n, node_to_id, id_counter))
+                .transpose()
+        })
+        .collect()
 }
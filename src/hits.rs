@@ -0,0 +1,122 @@
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+use crate::graph_utils::{
+    build_graph, node_dtype, scale_to_sum, with_id_type, with_node_columns, AsUsize, IngestOptions,
+    NodeColumn, NullPolicy, SelfLoops,
+};
+
+#[derive(Deserialize)]
+struct HitsKwargs {
+    max_iterations: u16,
+    convergence_threshold: f64,
+    null_policy: NullPolicy,
+    dedupe_edges: bool,
+    self_loops: SelfLoops,
+}
+
+impl HitsKwargs {
+    fn ingest_options(&self) -> IngestOptions {
+        IngestOptions {
+            null_policy: self.null_policy,
+            dedupe_edges: self.dedupe_edges,
+            self_loops: self.self_loops,
+            directed: true,
+        }
+    }
+}
+
+fn hits_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_dtype = node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]);
+
+    Ok(Field::new(
+        PlSmallStr::from("hits"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("node"), node_dtype),
+            Field::new(PlSmallStr::from("hub"), DataType::Float64),
+            Field::new(PlSmallStr::from("authority"), DataType::Float64),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=hits_output)]
+fn hits(inputs: &[Series], kwargs: HitsKwargs) -> PolarsResult<Series> {
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            calculate_hits::<_, NodeId>(from, to, &kwargs)
+        })
+    })
+}
+
+// Kleinberg's HITS, one row per distinct node in order of first appearance
+fn calculate_hits<C, T>(from: &C, to: &C, kwargs: &HitsKwargs) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = build_graph::<C, T>(from, to, None, kwargs.ingest_options())?;
+    let num_nodes = graph.num_nodes();
+    let outgoing_edges = graph.adjacency();
+
+    let mut hubs = vec![1.0 / num_nodes as f64; num_nodes];
+    let mut authorities = vec![0.0; num_nodes];
+    let mut new_hubs = vec![0.0; num_nodes];
+    let mut iterations = 0;
+
+    while iterations < kwargs.max_iterations {
+        iterations += 1;
+
+        // A node is as good an authority as the hubs pointing to it, and as good a hub as the
+        // authorities it points to
+        authorities.fill(0.0);
+        for (node, &hub) in hubs.iter().enumerate() {
+            for &to_id in outgoing_edges.neighbors(node) {
+                authorities[to_id.as_usize()] += hub;
+            }
+        }
+        for (node, new_hub) in new_hubs.iter_mut().enumerate() {
+            *new_hub = outgoing_edges
+                .neighbors(node)
+                .iter()
+                .fold(0.0, |hub, &to_id| hub + authorities[to_id.as_usize()]);
+        }
+
+        // Scale by the largest score so the scores don't grow without bound
+        scale_to_max(&mut new_hubs);
+        scale_to_max(&mut authorities);
+
+        let total_diff: f64 = new_hubs
+            .iter()
+            .zip(&hubs)
+            .map(|(new_hub, hub)| (new_hub - hub).abs())
+            .sum();
+        std::mem::swap(&mut hubs, &mut new_hubs);
+
+        if total_diff < kwargs.convergence_threshold {
+            break;
+        }
+    }
+
+    scale_to_sum(&mut hubs);
+    scale_to_sum(&mut authorities);
+
+    let fields = vec![
+        from.nodes_to_series(PlSmallStr::from("node"), &graph.nodes())?,
+        Series::new(PlSmallStr::from("hub"), hubs),
+        Series::new(PlSmallStr::from("authority"), authorities),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("hits"), &fields).map(|ca| ca.into_series())
+}
+
+// Scores stay 0 when every score is 0, as in a graph without edges
+fn scale_to_max(scores: &mut [f64]) {
+    let max = scores.iter().copied().fold(0.0, f64::max);
+    if max > 0.0 {
+        scores.iter_mut().for_each(|score| *score /= max);
+    }
+}
@@ -2,8 +2,10 @@ mod association_rule_graph_mining;
 mod graph_betweenness_centrality;
 mod graph_solver;
 mod graph_utils;
+mod hits;
 mod page_rank;
 mod shortest_path;
+mod strongly_connected_components;
 
 use pyo3::types::{PyModule, PyModuleMethods};
 use pyo3::{pymodule, Bound, PyResult};
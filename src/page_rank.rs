@@ -1,129 +1,634 @@
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
+use rayon::prelude::*;
 use serde::Deserialize;
 use std::convert::TryFrom;
 
-use crate::graph_utils::{process_edges, to_string_chunked, AsUsize};
+use crate::graph_utils::{
+    build_graph, node_dtype, scale_to_sum, to_float64_chunked, with_id_type, with_node_columns,
+    AsUsize, Csr, Graph, IngestOptions, NodeColumn, NullPolicy, SelfLoops,
+};
+
+// Rows across all partitions from which `page_rank_grouped` ranks the partitions in parallel
+const PARALLEL_THRESHOLD: usize = 1 << 16;
+// Fewest nodes a thread updates at once with `Method::Parallel`
+const PARALLEL_CHUNK_SIZE: usize = 1 << 12;
 
 #[derive(Deserialize)]
 struct PageRankKwargs {
     damping_factor: f64,
     max_iterations: u16,
     convergence_threshold: f64,
+    null_policy: NullPolicy,
+    dedupe_edges: bool,
+    self_loops: SelfLoops,
+    // Whether the edge weights are the third input, the personalization inputs come after them
+    weighted: bool,
+    tolerance_norm: ToleranceNorm,
+    method: Method,
+}
+
+impl PageRankKwargs {
+    fn ingest_options(&self) -> IngestOptions {
+        IngestOptions {
+            null_policy: self.null_policy,
+            dedupe_edges: self.dedupe_edges,
+            self_loops: self.self_loops,
+            directed: true,
+        }
+    }
+}
+
+/// Iteration that solves for the scores, all of them converge to the same scores.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Method {
+    /// Every node pulls rank over its incoming edges from the scores of the last iteration.
+    Power,
+    /// Every node pushes its rank along its outgoing edges, with the share per unit of outgoing
+    /// weight computed once per iteration.
+    Push,
+    /// Pulls with precomputed shares and updates the scores in place, so nodes later in a sweep
+    /// already pull the new scores. Usually needs fewer iterations.
+    GaussSeidel,
+    /// Pulls with precomputed shares, with the nodes of every iteration split over threads.
+    Parallel,
+}
+
+/// Norm of the change in scores between two iterations that is compared to the convergence
+/// threshold.
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum ToleranceNorm {
+    /// Sum of the absolute changes, grows with the number of nodes.
+    L1,
+    /// Euclidean length of the changes.
+    L2,
+    /// Largest absolute change of any node.
+    Inf,
+}
+
+impl ToleranceNorm {
+    fn residual(self, new_ranks: &[f64], page_ranks: &[f64]) -> f64 {
+        let changes = new_ranks
+            .iter()
+            .zip(page_ranks)
+            .map(|(new_rank, rank)| (new_rank - rank).abs());
+        match self {
+            ToleranceNorm::L1 => changes.sum(),
+            ToleranceNorm::L2 => changes.map(|change| change * change).sum::<f64>().sqrt(),
+            ToleranceNorm::Inf => changes.fold(0.0, f64::max),
+        }
+    }
+}
+
+// How the power iteration ended, reported by `page_rank_nodes`
+struct Convergence {
+    iterations: u32,
+    // Norm of the change in the last iteration, infinite if no iteration ran
+    residual: f64,
+    converged: bool,
+}
+
+// Rank is split in proportion to weights, so they have to be finite and non-negative. Null
+// weights are skipped.
+fn non_negative_weights(weights: &Series, name: &str) -> PolarsResult<Float64Chunked> {
+    let weights = to_float64_chunked(weights)?;
+
+    let invalid = weights.iter().enumerate().find_map(|(row, weight)| {
+        weight
+            .filter(|w| !w.is_finite() || *w < 0.0)
+            .map(|w| (row, w))
+    });
+    if let Some((row, weight)) = invalid {
+        polars_bail!(
+            ComputeError: "page_rank {} must be finite and non-negative, row {} has weight {}",
+            name, row, weight
+        );
+    }
+
+    Ok(weights)
+}
+
+// The inputs after the edge columns: edge weights, the personalization nodes and their weights
+type OptionalInputs = (
+    Option<Float64Chunked>,
+    Option<Series>,
+    Option<Float64Chunked>,
+);
+
+fn optional_inputs(inputs: &[Series], kwargs: &PageRankKwargs) -> PolarsResult<OptionalInputs> {
+    let weights = if kwargs.weighted {
+        Some(non_negative_weights(&inputs[2], "weights")?)
+    } else {
+        None
+    };
+    if let Some(weights) = &weights {
+        polars_ensure!(
+            weights.len() == inputs[0].len(),
+            ShapeMismatch: "page_rank weights and edges differ in length: {} != {}",
+            weights.len(),
+            inputs[0].len()
+        );
+    }
+    let personalization_index = 2 + usize::from(kwargs.weighted);
+
+    // The personalization nodes are looked up in the graph, so they take the dtype of its nodes
+    let node_type = node_dtype(&[inputs[0].dtype(), inputs[1].dtype()]);
+    let seeds = inputs
+        .get(personalization_index)
+        .map(|seeds| seeds.strict_cast(&node_type))
+        .transpose()?;
+
+    let seed_weights = inputs
+        .get(personalization_index + 1)
+        .map(|seed_weights| non_negative_weights(seed_weights, "personalization_weights"))
+        .transpose()?;
+    if let (Some(seeds), Some(seed_weights)) = (&seeds, &seed_weights) {
+        polars_ensure!(
+            seeds.len() == seed_weights.len(),
+            ShapeMismatch: "personalization nodes and weights differ in length: {} != {}",
+            seeds.len(),
+            seed_weights.len()
+        );
+    }
+
+    Ok((weights, seeds, seed_weights))
 }
 
 #[polars_expr(output_type = Float64)]
 fn page_rank(inputs: &[Series], kwargs: PageRankKwargs) -> PolarsResult<Series> {
-    let from = to_string_chunked(&inputs[0])?;
-    let to = to_string_chunked(&inputs[1])?;
-    let damping_factor = kwargs.damping_factor;
-    let max_iterations = kwargs.max_iterations;
-    let convergence_threshold = kwargs.convergence_threshold;
+    let (weights, seeds, seed_weights) = optional_inputs(inputs, &kwargs)?;
+    let node_inputs: Vec<&Series> = [&inputs[0], &inputs[1]].into_iter().chain(&seeds).collect();
 
-    let len = from.len();
-
-    if len <= u16::MAX as usize {
-        calculate_pagerank::<u16>(
-            &from,
-            &to,
-            damping_factor,
-            max_iterations,
-            convergence_threshold,
-        )
-    } else if len <= u32::MAX as usize {
-        calculate_pagerank::<u32>(
-            &from,
-            &to,
-            damping_factor,
-            max_iterations,
-            convergence_threshold,
-        )
-    } else {
-        calculate_pagerank::<u64>(
-            &from,
-            &to,
-            damping_factor,
-            max_iterations,
-            convergence_threshold,
-        )
+    with_node_columns!(&node_inputs, |columns| {
+        let (from, to, seeds) = (&columns[0], &columns[1], columns.get(2));
+        let scores = with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            calculate_pagerank::<_, NodeId>(
+                from,
+                to,
+                weights.as_ref(),
+                seeds,
+                seed_weights.as_ref(),
+                &kwargs,
+            )
+        })?;
+        Ok(Float64Chunked::from_vec("pagerank".into(), scores).into_series())
+    })
+}
+
+// PageRank of every partition on its own, the first input is the partition key. Rows with a null
+// key form a partition of their own.
+#[polars_expr(output_type = Float64)]
+fn page_rank_grouped(inputs: &[Series], kwargs: PageRankKwargs) -> PolarsResult<Series> {
+    let (partition, edge_inputs) = (&inputs[0], &inputs[1..]);
+    let (weights, _, _) = optional_inputs(edge_inputs, &kwargs)?;
+    polars_ensure!(
+        partition.len() == edge_inputs[0].len(),
+        ShapeMismatch: "page_rank partition key and edges differ in length: {} != {}",
+        partition.len(),
+        edge_inputs[0].len()
+    );
+
+    // The rows are reordered below, so a null node has to be reported at its original row
+    if kwargs.null_policy == NullPolicy::Error {
+        let (from_nulls, to_nulls) = (edge_inputs[0].is_null(), edge_inputs[1].is_null());
+        let first_null = from_nulls
+            .iter()
+            .zip(to_nulls.iter())
+            .position(|(from_null, to_null)| from_null == Some(true) || to_null == Some(true));
+        if let Some(row) = first_null {
+            let node = |nulls: &BooleanChunked| (nulls.get(row) != Some(true)).then_some(());
+            NullPolicy::Error.edge(row, node(&from_nulls), node(&to_nulls))?;
+        }
+    }
+
+    // Gather the rows of every partition next to each other, so each subgraph is a slice
+    let groups = partition.group_tuples(true, false)?;
+    let mut order: Vec<IdxSize> = Vec::with_capacity(partition.len());
+    let mut ranges = Vec::with_capacity(groups.len());
+    for group in groups.iter() {
+        let offset = order.len();
+        match group {
+            GroupsIndicator::Idx((_, rows)) => order.extend_from_slice(rows),
+            GroupsIndicator::Slice([first, len]) => order.extend(first..first + len),
+        }
+        ranges.push((offset, order.len() - offset));
+    }
+    let order = IdxCa::from_vec("order".into(), order);
+    let from = edge_inputs[0].take(&order)?;
+    let to = edge_inputs[1].take(&order)?;
+    let weights = weights.map(|weights| weights.take(&order)).transpose()?;
+
+    let scores = with_node_columns!(&[&from, &to], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        let rank_partition = |&(offset, len): &(usize, usize)| {
+            let from = from.slice_rows(offset, len);
+            let to = to.slice_rows(offset, len);
+            let weights = weights
+                .as_ref()
+                .map(|weights| weights.slice(offset as i64, len));
+            with_id_type!(2 * len, |NodeId| {
+                calculate_pagerank::<_, NodeId>(&from, &to, weights.as_ref(), None, None, &kwargs)
+            })
+        };
+
+        if from.num_rows() >= PARALLEL_THRESHOLD && rayon::current_num_threads() > 1 {
+            ranges
+                .par_iter()
+                .map(rank_partition)
+                .collect::<PolarsResult<Vec<_>>>()
+        } else {
+            ranges
+                .iter()
+                .map(rank_partition)
+                .collect::<PolarsResult<Vec<_>>>()
+        }
+    })?;
+
+    // Scatter the scores of the gathered rows back to their original positions
+    let mut page_ranks = vec![0.0; partition.len()];
+    for (row, score) in order.into_no_null_iter().zip(scores.into_iter().flatten()) {
+        page_ranks[row as usize] = score;
     }
+
+    Ok(Float64Chunked::from_vec("pagerank".into(), page_ranks).into_series())
 }
 
-fn calculate_pagerank<T>(
-    from: &StringChunked,
-    to: &StringChunked,
-    damping_factor: f64,
-    max_iterations: u16,
-    convergence_threshold: f64,
-) -> PolarsResult<Series>
+fn page_rank_nodes_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_dtype = node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]);
+
+    Ok(Field::new(
+        PlSmallStr::from("pagerank"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("node"), node_dtype),
+            Field::new(PlSmallStr::from("score"), DataType::Float64),
+            Field::new(PlSmallStr::from("in_degree"), DataType::UInt64),
+            Field::new(PlSmallStr::from("out_degree"), DataType::UInt64),
+            Field::new(PlSmallStr::from("iterations"), DataType::UInt32),
+            Field::new(PlSmallStr::from("residual"), DataType::Float64),
+            Field::new(PlSmallStr::from("converged"), DataType::Boolean),
+        ]),
+    ))
+}
+
+#[polars_expr(output_type_func=page_rank_nodes_output)]
+fn page_rank_nodes(inputs: &[Series], kwargs: PageRankKwargs) -> PolarsResult<Series> {
+    let (weights, seeds, seed_weights) = optional_inputs(inputs, &kwargs)?;
+    let node_inputs: Vec<&Series> = [&inputs[0], &inputs[1]].into_iter().chain(&seeds).collect();
+
+    with_node_columns!(&node_inputs, |columns| {
+        let (from, to, seeds) = (&columns[0], &columns[1], columns.get(2));
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            calculate_pagerank_nodes::<_, NodeId>(
+                from,
+                to,
+                weights.as_ref(),
+                seeds,
+                seed_weights.as_ref(),
+                &kwargs,
+            )
+        })
+    })
+}
+
+// Solves for the scores with the iteration of `kwargs.method`, returns the scores and how the
+// iteration ended
+fn rank_nodes<K, T>(
+    graph: &Graph<K, T>,
+    teleport: Option<&[f64]>,
+    kwargs: &PageRankKwargs,
+) -> (Vec<f64>, Convergence)
 where
-    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    T: TryFrom<usize> + Copy + AsUsize + Sync,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    // Use process_edges from graph_utils
-    let (node_to_id, id_counter, edges) = process_edges::<T>(from, to)?;
-    let num_nodes = id_counter.as_usize();
-
-    // Create adjacency list representation
-    let mut outgoing_edges: Vec<Vec<T>> = vec![Vec::new(); num_nodes];
-    let mut incoming_edges: Vec<Vec<T>> = vec![Vec::new(); num_nodes];
-
-    // Process edges using the SmallVec from process_edges
-    for &(from_id, to_id) in edges.iter() {
-        outgoing_edges[from_id.as_usize()].push(to_id);
-        incoming_edges[to_id.as_usize()].push(from_id);
+    let damping_factor = kwargs.damping_factor;
+    let num_nodes = graph.num_nodes();
+    let out_weights = out_weights(graph);
+    let jump_share = |node: usize, jump_rank: f64| match teleport {
+        Some(teleport) => jump_rank * teleport[node],
+        None => jump_rank / num_nodes as f64,
+    };
+
+    match kwargs.method {
+        Method::Power => {
+            let incoming_edges = graph.incoming_adjacency();
+            iterate(&out_weights, kwargs, |page_ranks, new_ranks, jump_rank| {
+                // The share of a source is the weight of the edge over its total outgoing weight,
+                // unweighted edges all have weight 1
+                for (node, new_rank) in new_ranks.iter_mut().enumerate() {
+                    let sources = incoming_edges.neighbors(node);
+                    let weights = incoming_edges.neighbor_weights(node);
+                    let share = |from: usize, weight: f64| {
+                        if out_weights[from] > 0.0 {
+                            page_ranks[from] * weight / out_weights[from]
+                        } else {
+                            0.0
+                        }
+                    };
+
+                    let incoming_rank: f64 = if weights.is_empty() {
+                        sources
+                            .iter()
+                            .map(|&from| share(from.as_usize(), 1.0))
+                            .sum()
+                    } else {
+                        sources
+                            .iter()
+                            .zip(weights)
+                            .map(|(&from, &weight)| share(from.as_usize(), weight))
+                            .sum()
+                    };
+
+                    *new_rank = jump_share(node, jump_rank) + damping_factor * incoming_rank;
+                }
+            })
+        },
+        Method::Push => {
+            let outgoing_edges = graph.adjacency();
+            let inverse_out_weights = inverse_out_weights(&out_weights);
+            iterate(&out_weights, kwargs, |page_ranks, new_ranks, jump_rank| {
+                for (node, new_rank) in new_ranks.iter_mut().enumerate() {
+                    *new_rank = jump_share(node, jump_rank);
+                }
+                // A source hands the same share of its rank to every unit of outgoing weight
+                for (from, &rank) in page_ranks.iter().enumerate() {
+                    let share = damping_factor * rank * inverse_out_weights[from];
+                    if share == 0.0 {
+                        continue;
+                    }
+                    let targets = outgoing_edges.neighbors(from);
+                    let weights = outgoing_edges.neighbor_weights(from);
+                    if weights.is_empty() {
+                        for &to in targets {
+                            new_ranks[to.as_usize()] += share;
+                        }
+                    } else {
+                        for (&to, &weight) in targets.iter().zip(weights) {
+                            new_ranks[to.as_usize()] += share * weight;
+                        }
+                    }
+                }
+            })
+        },
+        Method::GaussSeidel => {
+            let incoming_edges = graph.incoming_adjacency();
+            let inverse_out_weights = inverse_out_weights(&out_weights);
+            iterate(&out_weights, kwargs, |page_ranks, new_ranks, jump_rank| {
+                // Sources earlier in the sweep already pass on their updated rank
+                new_ranks.copy_from_slice(page_ranks);
+                for node in 0..num_nodes {
+                    let incoming_rank =
+                        incoming_rank(&incoming_edges, &inverse_out_weights, node, new_ranks);
+                    new_ranks[node] = jump_share(node, jump_rank) + damping_factor * incoming_rank;
+                }
+                // The jumps are spread from the scores before the sweep, so the updated scores
+                // drift from summing to 1 until they are rescaled
+                scale_to_sum(new_ranks);
+            })
+        },
+        Method::Parallel => {
+            let incoming_edges = graph.incoming_adjacency();
+            let inverse_out_weights = inverse_out_weights(&out_weights);
+            iterate(&out_weights, kwargs, |page_ranks, new_ranks, jump_rank| {
+                new_ranks
+                    .par_iter_mut()
+                    .with_min_len(PARALLEL_CHUNK_SIZE)
+                    .enumerate()
+                    .for_each(|(node, new_rank)| {
+                        let incoming_rank =
+                            incoming_rank(&incoming_edges, &inverse_out_weights, node, page_ranks);
+                        *new_rank = jump_share(node, jump_rank) + damping_factor * incoming_rank;
+                    });
+            })
+        },
     }
+}
+
+// Runs `sweep` from uniform scores until the change in scores is below the convergence threshold.
+// A sweep gets the previous scores, the scores to overwrite and the rank that jumps instead of
+// following an edge.
+fn iterate(
+    out_weights: &[f64],
+    kwargs: &PageRankKwargs,
+    mut sweep: impl FnMut(&[f64], &mut [f64], f64),
+) -> (Vec<f64>, Convergence) {
+    let damping_factor = kwargs.damping_factor;
+    let convergence_threshold = kwargs.convergence_threshold;
+    let num_nodes = out_weights.len();
+
+    // Nodes without outgoing weight spread their rank like a random jump, so the scores keep
+    // summing to 1
+    let dangling_nodes: Vec<usize> = (0..num_nodes)
+        .filter(|&node| out_weights[node] == 0.0)
+        .collect();
 
     // Initialize PageRank scores
     let mut page_ranks: Vec<f64> = vec![1.0 / num_nodes as f64; num_nodes];
     let mut new_ranks: Vec<f64> = vec![0.0; num_nodes];
+    let mut iterations = 0;
+    let mut residual = f64::INFINITY;
 
     // PageRank iteration
-    for _ in 0..max_iterations {
-        let mut total_diff = 0.0;
-        new_ranks.fill(0.0);
+    while iterations < u32::from(kwargs.max_iterations) {
+        iterations += 1;
 
-        // Calculate new rank for each node
-        for node in 0..num_nodes {
-            let base_rank = (1.0 - damping_factor) / num_nodes as f64;
+        let dangling_rank: f64 = dangling_nodes.iter().map(|&node| page_ranks[node]).sum();
+        let jump_rank = (1.0 - damping_factor) + damping_factor * dangling_rank;
+        sweep(&page_ranks, &mut new_ranks, jump_rank);
 
-            let incoming_rank: f64 = incoming_edges[node]
-                .iter()
-                .map(|&from| {
-                    let out_count = outgoing_edges[from.as_usize()].len() as f64;
-                    if out_count > 0.0 {
-                        page_ranks[from.as_usize()] / out_count
-                    } else {
-                        0.0
-                    }
-                })
-                .sum();
+        residual = kwargs.tolerance_norm.residual(&new_ranks, &page_ranks);
+        std::mem::swap(&mut page_ranks, &mut new_ranks);
 
-            new_ranks[node] = base_rank + damping_factor * incoming_rank;
+        if residual < convergence_threshold {
+            break;
         }
+    }
 
-        // Calculate total difference for convergence check
-        for i in 0..num_nodes {
-            total_diff += (new_ranks[i] - page_ranks[i]).abs();
-        }
+    let convergence = Convergence {
+        iterations,
+        residual,
+        converged: residual < convergence_threshold,
+    };
+    (page_ranks, convergence)
+}
 
-        page_ranks.copy_from_slice(&new_ranks);
+// Rank that flows into `node` over its incoming edges
+fn incoming_rank<T>(
+    incoming_edges: &Csr<T>,
+    inverse_out_weights: &[f64],
+    node: usize,
+    page_ranks: &[f64],
+) -> f64
+where
+    T: TryFrom<usize> + Copy + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let sources = incoming_edges.neighbors(node);
+    let weights = incoming_edges.neighbor_weights(node);
+    let source_rank = |from: T| {
+        let from = from.as_usize();
+        page_ranks[from] * inverse_out_weights[from]
+    };
 
-        if total_diff < convergence_threshold {
-            break;
+    if weights.is_empty() {
+        sources.iter().map(|&from| source_rank(from)).sum()
+    } else {
+        sources
+            .iter()
+            .zip(weights)
+            .map(|(&from, &weight)| source_rank(from) * weight)
+            .sum()
+    }
+}
+
+// Teleport probability of every node, proportional to its personalization weight. Duplicate
+// personalization nodes add up, nodes that aren't in the graph are ignored.
+fn teleport_vector<'a, C, T>(
+    graph: &Graph<C::Key<'a>, T>,
+    seeds: &'a C,
+    seed_weights: Option<&Float64Chunked>,
+) -> PolarsResult<Vec<f64>>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let mut teleport = vec![0.0; graph.num_nodes()];
+    for (row, seed) in seeds.keys().enumerate() {
+        let weight = seed_weights.map_or(Some(1.0), |seed_weights| seed_weights.get(row));
+        let id = seed.and_then(|seed| graph.node_to_id.get(&seed));
+        if let (Some(&id), Some(weight)) = (id, weight) {
+            teleport[id.as_usize()] += weight;
         }
     }
 
-    // Map the PageRank scores back to the original nodes using node_to_id from process_edges
-    let scores: Vec<f64> = from
+    let total: f64 = teleport.iter().sum();
+    polars_ensure!(
+        total > 0.0 || graph.num_nodes() == 0,
+        ComputeError: "page_rank personalization has no weight on any node of the graph"
+    );
+    teleport.iter_mut().for_each(|weight| *weight /= total);
+
+    Ok(teleport)
+}
+
+// Total weight of the outgoing edges of every node, its out-degree for unweighted graphs
+fn out_weights<K, T>(graph: &Graph<K, T>) -> Vec<f64>
+where
+    T: TryFrom<usize> + Copy + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let mut out_weights = vec![0.0; graph.num_nodes()];
+    match &graph.weights {
+        Some(weights) => {
+            for (&(from_id, _), &weight) in graph.edges.iter().zip(weights) {
+                out_weights[from_id.as_usize()] += weight;
+            }
+        },
+        None => {
+            for &(from_id, _) in &graph.edges {
+                out_weights[from_id.as_usize()] += 1.0;
+            }
+        },
+    }
+    out_weights
+}
+
+// Share of a node's rank per unit of outgoing weight, 0 for nodes without outgoing weight
+fn inverse_out_weights(out_weights: &[f64]) -> Vec<f64> {
+    out_weights
         .iter()
-        .map(|from_node| {
-            from_node
-                .and_then(|node| node_to_id.get(node))
+        .map(|&weight| if weight > 0.0 { 1.0 / weight } else { 0.0 })
+        .collect()
+}
+
+fn calculate_pagerank<C, T>(
+    from: &C,
+    to: &C,
+    weights: Option<&Float64Chunked>,
+    seeds: Option<&C>,
+    seed_weights: Option<&Float64Chunked>,
+    kwargs: &PageRankKwargs,
+) -> PolarsResult<Vec<f64>>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64> + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = build_graph::<C, T>(from, to, weights, kwargs.ingest_options())?;
+    let teleport = seeds
+        .map(|seeds| teleport_vector(&graph, seeds, seed_weights))
+        .transpose()?;
+
+    let (page_ranks, _) = rank_nodes(&graph, teleport.as_deref(), kwargs);
+
+    // Map the PageRank scores back to the original nodes using the node ids of the graph
+    let scores: Vec<f64> = from
+        .keys()
+        .zip(to.keys())
+        .map(|(from_node, to_node)| {
+            kwargs
+                .null_policy
+                .row_nodes(from_node, to_node)
+                .0
+                .and_then(|node| graph.node_to_id.get(&node))
                 .map(|&id| page_ranks[id.as_usize()])
                 .unwrap_or(0.0)
         })
         .collect();
 
-    Ok(Float64Chunked::from_vec("pagerank".into(), scores).into_series())
+    Ok(scores)
+}
+
+// One row per distinct node in order of first appearance, including nodes only seen in `to`
+fn calculate_pagerank_nodes<C, T>(
+    from: &C,
+    to: &C,
+    weights: Option<&Float64Chunked>,
+    seeds: Option<&C>,
+    seed_weights: Option<&Float64Chunked>,
+    kwargs: &PageRankKwargs,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64> + Sync,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = build_graph::<C, T>(from, to, weights, kwargs.ingest_options())?;
+    let teleport = seeds
+        .map(|seeds| teleport_vector(&graph, seeds, seed_weights))
+        .transpose()?;
+    let num_nodes = graph.num_nodes();
+
+    let (page_ranks, convergence) = rank_nodes(&graph, teleport.as_deref(), kwargs);
+
+    let mut in_degrees = vec![0u64; num_nodes];
+    let mut out_degrees = vec![0u64; num_nodes];
+    for &(from_id, to_id) in &graph.edges {
+        out_degrees[from_id.as_usize()] += 1;
+        in_degrees[to_id.as_usize()] += 1;
+    }
+
+    let fields = vec![
+        from.nodes_to_series(PlSmallStr::from("node"), &graph.nodes())?,
+        Series::new(PlSmallStr::from("score"), page_ranks),
+        Series::new(PlSmallStr::from("in_degree"), in_degrees),
+        Series::new(PlSmallStr::from("out_degree"), out_degrees),
+        Series::new(
+            PlSmallStr::from("iterations"),
+            vec![convergence.iterations; num_nodes],
+        ),
+        Series::new(
+            PlSmallStr::from("residual"),
+            vec![convergence.residual; num_nodes],
+        ),
+        Series::new(
+            PlSmallStr::from("converged"),
+            vec![convergence.converged; num_nodes],
+        ),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("pagerank"), &fields).map(|ca| ca.into_series())
 }
@@ -1,20 +1,31 @@
-use crate::graph_utils::{to_float64_chunked, to_string_chunked, usize_to_t, AsUsize};
+use crate::graph_utils::{
+    build_graph, node_dtype, to_float64_chunked, with_id_type, with_node_columns, AsUsize, Csr,
+    IngestOptions, NodeColumn, NullPolicy, SelfLoops,
+};
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
 use serde::Deserialize;
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::BinaryHeap;
 use std::convert::TryFrom;
 
-// Type aliases to reduce complexity
-type NodeMap<T> = HashMap<String, T>;
-type Edge<T> = (T, T, i64);
-type EdgeList<T> = Vec<Edge<T>>;
-type ProcessResult<T> = PolarsResult<(NodeMap<T>, T, EdgeList<T>)>;
-
 #[derive(Deserialize)]
 struct ShortestPathKwargs {
     directed: bool,
+    null_policy: NullPolicy,
+    dedupe_edges: bool,
+    self_loops: SelfLoops,
+}
+
+impl ShortestPathKwargs {
+    fn ingest_options(&self) -> IngestOptions {
+        IngestOptions {
+            null_policy: self.null_policy,
+            dedupe_edges: self.dedupe_edges,
+            self_loops: self.self_loops,
+            directed: self.directed,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -35,52 +46,12 @@ impl PartialOrd for State {
     }
 }
 
-fn process_edges_with_weights<T>(
-    from: StringChunked,
-    to: StringChunked,
-    weights: Float64Chunked,
-) -> ProcessResult<T>
-where
-    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
-    <T as TryFrom<usize>>::Error: std::fmt::Debug,
-{
-    let estimated_size = from.len();
-    let mut node_to_id = HashMap::with_capacity(estimated_size);
-    let mut edges = Vec::with_capacity(estimated_size);
-    let mut id_counter: T = usize_to_t(0);
-
-    let mut process_node = |node: &str| -> T {
-        if let Some(&id) = node_to_id.get(node) {
-            id
-        } else {
-            let id = id_counter;
-            node_to_id.insert(node.to_string(), id);
-            id_counter = usize_to_t(id_counter.as_usize() + 1);
-            id
-        }
-    };
-
-    from.iter()
-        .zip(to.iter())
-        .zip(weights.iter())
-        .try_for_each(|((from_node, to_node), weight)| -> PolarsResult<()> {
-            if let (Some(f), Some(t), Some(w)) = (from_node, to_node, weight) {
-                let f_id = process_node(f);
-                let t_id = process_node(t);
-                edges.push((f_id, t_id, (w * 1000.0) as i64));
-            }
-            Ok(())
-        })?;
-
-    Ok((node_to_id, id_counter, edges))
-}
-
-fn shortest_path<T>(start_id: usize, target_id: usize, adj_list: &[Vec<(usize, i64)>]) -> f64
+fn shortest_path<T>(start_id: usize, target_id: usize, adjacency: &Csr<T>) -> f64
 where
     T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
     <T as TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    let num_nodes = adj_list.len();
+    let num_nodes = adjacency.num_nodes();
     let mut dist = vec![i64::MAX; num_nodes];
     dist[start_id] = 0;
 
@@ -99,8 +70,11 @@ where
             continue;
         }
 
-        for &(neighbor, weight) in &adj_list[position] {
-            let next_cost = cost + weight;
+        let neighbors = adjacency.neighbors(position);
+        let weights = adjacency.neighbor_weights(position);
+        for (&neighbor, &weight) in neighbors.iter().zip(weights) {
+            let neighbor = neighbor.as_usize();
+            let next_cost = cost + (weight * 1000.0) as i64;
             if next_cost < dist[neighbor] {
                 dist[neighbor] = next_cost;
                 heap.push(State {
@@ -114,12 +88,13 @@ where
     f64::INFINITY
 }
 
-fn shortest_path_output(_: &[Field]) -> PolarsResult<Field> {
+fn shortest_path_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let node_type = node_dtype(&[input_fields[0].dtype(), input_fields[1].dtype()]);
     Ok(Field::new(
         "shortest_paths".into(),
         DataType::Struct(vec![
-            Field::new("from".into(), DataType::String),
-            Field::new("to".into(), DataType::String),
+            Field::new("from".into(), node_type.clone()),
+            Field::new("to".into(), node_type),
             Field::new("distance".into(), DataType::Float64),
         ]),
     ))
@@ -128,68 +103,80 @@ fn shortest_path_output(_: &[Field]) -> PolarsResult<Field> {
 
 #[polars_expr(output_type_func=shortest_path_output)]
 fn graph_find_shortest_path(inputs: &[Series], kwargs: ShortestPathKwargs) -> PolarsResult<Series> {
-    let from = to_string_chunked(&inputs[0])?;
-    let to = to_string_chunked(&inputs[1])?;
     let weights = to_float64_chunked(&inputs[2])?;
+    polars_ensure!(
+        weights.len() == inputs[0].len(),
+        ShapeMismatch: "shortest path weights and edges differ in length: {} != {}",
+        weights.len(),
+        inputs[0].len()
+    );
+
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            find_shortest_paths::<_, NodeId>(from, to, &weights, &kwargs)
+        })
+    })
+}
 
-    type NodeId = u32;
-
-    let (node_to_id, id_counter, edges) = process_edges_with_weights::<NodeId>(from, to, weights)?;
-    let num_nodes = id_counter.as_usize();
-
-    let mut adj_list = vec![Vec::new(); num_nodes];
-    for (from_id, to_id, weight) in edges {
-        adj_list[from_id.as_usize()].push((to_id.as_usize(), weight));
-        if !kwargs.directed {
-            adj_list[to_id.as_usize()].push((from_id.as_usize(), weight));
-        }
-    }
+fn find_shortest_paths<C, T>(
+    from: &C,
+    to: &C,
+    weights: &Float64Chunked,
+    kwargs: &ShortestPathKwargs,
+) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize + Into<u64>,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let directed = kwargs.directed;
+    let graph = build_graph::<C, T>(from, to, Some(weights), kwargs.ingest_options())?;
+    let adjacency = graph.adjacency();
+    let num_nodes = graph.num_nodes();
 
-    let estimated_pairs = if kwargs.directed {
-        num_nodes * (num_nodes - 1)
+    let estimated_pairs = if directed {
+        num_nodes * num_nodes.saturating_sub(1)
     } else {
-        (num_nodes * (num_nodes - 1)) / 2
+        (num_nodes * num_nodes.saturating_sub(1)) / 2
     };
 
     let mut from_nodes = Vec::with_capacity(estimated_pairs);
     let mut to_nodes = Vec::with_capacity(estimated_pairs);
     let mut distances = Vec::with_capacity(estimated_pairs);
 
-    let mut node_ids: Vec<(&String, usize)> = node_to_id
-        .iter()
-        .map(|(name, &id)| (name, id.as_usize()))
+    // Pairs are visited in sorted order, so for undirected graphs the smaller node comes first
+    let mut node_ids: Vec<(&C::Key<'_>, usize)> = graph
+        .nodes()
+        .into_iter()
+        .enumerate()
+        .map(|(id, node)| (node, id))
         .collect();
     node_ids.sort_by(|a, b| a.0.cmp(b.0));
 
     for i in 0..node_ids.len() {
-        for j in (if kwargs.directed { 0 } else { i + 1 })..node_ids.len() {
+        for j in (if directed { 0 } else { i + 1 })..node_ids.len() {
             if i == j {
                 continue;
             }
 
-            let (start_name, start_id) = node_ids[i];
-            let (target_name, target_id) = node_ids[j];
+            let (start_node, start_id) = node_ids[i];
+            let (target_node, target_id) = node_ids[j];
 
-            let distance = shortest_path::<NodeId>(start_id, target_id, &adj_list);
+            let distance = shortest_path::<T>(start_id, target_id, &adjacency);
 
             if distance != f64::INFINITY {
-                // For undirected graphs, always store lexicographically smaller node first
-                if !kwargs.directed && start_name > target_name {
-                    from_nodes.push(target_name.clone());
-                    to_nodes.push(start_name.clone());
-                } else {
-                    from_nodes.push(start_name.clone());
-                    to_nodes.push(target_name.clone());
-                }
+                from_nodes.push(start_node);
+                to_nodes.push(target_node);
                 distances.push(distance);
             }
 
-            if kwargs.directed {
-                let reverse_distance = shortest_path::<NodeId>(target_id, start_id, &adj_list);
+            if directed {
+                let reverse_distance = shortest_path::<T>(target_id, start_id, &adjacency);
 
                 if reverse_distance != f64::INFINITY {
-                    from_nodes.push(target_name.clone());
-                    to_nodes.push(start_name.clone());
+                    from_nodes.push(target_node);
+                    to_nodes.push(start_node);
                     distances.push(reverse_distance);
                 }
             }
@@ -197,8 +184,8 @@ fn graph_find_shortest_path(inputs: &[Series], kwargs: ShortestPathKwargs) -> Po
     }
 
     let fields = vec![
-        Series::new(PlSmallStr::from("from"), from_nodes),
-        Series::new(PlSmallStr::from("to"), to_nodes),
+        from.nodes_to_series(PlSmallStr::from("from"), &from_nodes)?,
+        from.nodes_to_series(PlSmallStr::from("to"), &to_nodes)?,
         Series::new(PlSmallStr::from("distance"), distances),
     ];
 
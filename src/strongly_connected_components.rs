@@ -0,0 +1,165 @@
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+use crate::graph_utils::{
+    process_edges, with_id_type, with_node_columns, AsUsize, Csr, NodeColumn, NullPolicy,
+};
+
+const UNVISITED: usize = usize::MAX;
+
+// Iterative version of Tarjan's algorithm, so deep dependency chains can't overflow the stack.
+// Returns the index of the strongly connected component for every node.
+pub(crate) fn tarjan_scc<T>(adjacency: &Csr<T>) -> Vec<usize>
+where
+    T: TryFrom<usize> + Copy + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let num_nodes = adjacency.num_nodes();
+
+    let mut index = vec![UNVISITED; num_nodes];
+    let mut low_link = vec![0; num_nodes];
+    let mut on_stack = vec![false; num_nodes];
+    let mut stack = Vec::new();
+    let mut components = vec![UNVISITED; num_nodes];
+    let mut index_counter = 0;
+    let mut component_counter = 0;
+
+    // Each frame holds a node and the position of the next outgoing edge to visit
+    let mut call_stack: Vec<(usize, usize)> = Vec::new();
+
+    for start in 0..num_nodes {
+        if index[start] != UNVISITED {
+            continue;
+        }
+
+        index[start] = index_counter;
+        low_link[start] = index_counter;
+        index_counter += 1;
+        stack.push(start);
+        on_stack[start] = true;
+        call_stack.push((start, 0));
+
+        while let Some((v, next_edge)) = call_stack.last_mut() {
+            let v = *v;
+            if let Some(w) = adjacency.neighbors(v).get(*next_edge).map(|w| w.as_usize()) {
+                *next_edge += 1;
+                if index[w] == UNVISITED {
+                    index[w] = index_counter;
+                    low_link[w] = index_counter;
+                    index_counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    call_stack.push((w, 0));
+                } else if on_stack[w] {
+                    low_link[v] = low_link[v].min(index[w]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                low_link[parent] = low_link[parent].min(low_link[v]);
+            }
+
+            // v is the root of a component, pop all of its members
+            if low_link[v] == index[v] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    components[w] = component_counter;
+                    if w == v {
+                        break;
+                    }
+                }
+                component_counter += 1;
+            }
+        }
+    }
+
+    components
+}
+
+// Number the components by the order in which their first node was seen, starting at 1
+pub(crate) fn number_components(components: &[usize]) -> Vec<u64> {
+    let mut component_ids = vec![0u64; components.len()];
+    let mut seen = vec![0u64; components.len()];
+    let mut counter = 1;
+
+    for (node, &component) in components.iter().enumerate() {
+        if seen[component] == 0 {
+            seen[component] = counter;
+            counter += 1;
+        }
+        component_ids[node] = seen[component];
+    }
+
+    component_ids
+}
+
+fn strongly_connected_components_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        PlSmallStr::from("strongly_connected_components"),
+        DataType::Struct(vec![
+            Field::new(PlSmallStr::from("component"), DataType::UInt64),
+            Field::new(PlSmallStr::from("in_cycle"), DataType::Boolean),
+        ]),
+    ))
+}
+
+#[derive(Deserialize)]
+struct StronglyConnectedComponentsKwargs {
+    null_policy: NullPolicy,
+}
+
+#[polars_expr(output_type_func=strongly_connected_components_output)]
+fn graph_strongly_connected_components(
+    inputs: &[Series],
+    kwargs: StronglyConnectedComponentsKwargs,
+) -> PolarsResult<Series> {
+    with_node_columns!(&[&inputs[0], &inputs[1]], |columns| {
+        let (from, to) = (&columns[0], &columns[1]);
+        with_id_type!(from.num_rows() + to.num_rows(), |NodeId| {
+            process_scc::<_, NodeId>(from, to, kwargs.null_policy)
+        })
+    })
+}
+
+fn process_scc<C, T>(from: &C, to: &C, null_policy: NullPolicy) -> PolarsResult<Series>
+where
+    C: NodeColumn,
+    T: TryFrom<usize> + Copy + PartialEq + AsUsize,
+    <T as TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let graph = process_edges::<C, T>(from, to, null_policy)?;
+
+    let components = tarjan_scc(&graph.adjacency());
+    let component_ids = number_components(&components);
+
+    // An edge lies on a cycle when both of its endpoints share a component (self-loops included)
+    let (row_components, in_cycle): (Vec<u64>, Vec<bool>) = from
+        .keys()
+        .zip(to.keys())
+        .map(|(from_node, to_node)| {
+            let (from_node, to_node) = null_policy.row_nodes(from_node, to_node);
+            let from_id = from_node.and_then(|node| graph.node_to_id.get(&node));
+            let to_id = to_node.and_then(|node| graph.node_to_id.get(&node));
+            match (from_id, to_id) {
+                (Some(&f), Some(&t)) => (
+                    component_ids[f.as_usize()],
+                    components[f.as_usize()] == components[t.as_usize()],
+                ),
+                (Some(&f), None) => (component_ids[f.as_usize()], false),
+                _ => (0, false),
+            }
+        })
+        .unzip();
+
+    let fields = vec![
+        Series::new(PlSmallStr::from("component"), row_components),
+        Series::new(PlSmallStr::from("in_cycle"), in_cycle),
+    ];
+
+    StructChunked::from_series(PlSmallStr::from("strongly_connected_components"), &fields)
+        .map(|ca| ca.into_series())
+}
@@ -3,7 +3,15 @@ import pytest
 from polars_grouper import (
     graph_solver,
     super_merger,
+    super_merger_weighted,
+    strongly_connected_components,
+    connected_components,
+    graph_component_summary,
+    graph_solver_nodes,
+    graph_solver_incremental,
+    graph_component_lineage,
     page_rank,
+    hits,
     calculate_shortest_path,
     betweenness_centrality,
     graph_association_rules,
@@ -15,29 +23,184 @@ def test_page_rank() -> None:
     """
     Test the page_rank function calculates correct PageRank values for a directed graph.
 
-    Tests a simple graph with multiple nodes and checks if the resulting rank values
-    match expected values computed manually.
+    Tests a graph with sink nodes, whose rank is spread over all nodes, and checks that the
+    resulting rank values match networkx.pagerank on the same edges.
     """
     df = pl.DataFrame(
         {"from": ["A", "B", "C", "E", "F", "G", "I", "I", "AA"], "to": ["B", "C", "D", "F", "G", "J", "K", "J", "Z"]}
     )
     result_df = df.select(page_rank(pl.col("from"), pl.col("to")).alias("rank"))
-    expected_df = pl.DataFrame(
+    # networkx.pagerank(nx.MultiDiGraph(edges)) of the source node of every row
+    expected = [0.043633, 0.080722, 0.112247, 0.043633, 0.080722, 0.112247, 0.043633, 0.043633, 0.043633]
+    assert result_df["rank"].to_list() == pytest.approx(expected, abs=1e-6)
+
+
+def test_page_rank_nodes() -> None:
+    """Test that page_rank with nodes=True scores every distinct node, including sinks only seen in `to`."""
+    df = pl.DataFrame({"from": [1, 2], "to": [2, 3]})
+
+    result = df.select(page_rank(pl.col("from"), pl.col("to"), nodes=True).alias("pagerank")).unnest("pagerank")
+
+    assert result.schema["node"] == pl.Int64
+    assert result["node"].to_list() == [1, 2, 3]
+    assert result["score"].to_list() == pytest.approx([0.184417, 0.341172, 0.474412], abs=1e-5)
+    assert result["score"].sum() == pytest.approx(1.0)
+    assert result["in_degree"].to_list() == [0, 1, 1]
+    assert result["out_degree"].to_list() == [1, 1, 0]
+    assert result["iterations"].to_list() == [20, 20, 20]
+    assert result["converged"].to_list() == [True, True, True]
+    assert result["residual"][0] < 1e-6
+
+
+def test_page_rank_convergence() -> None:
+    """Test that page_rank reports whether it converged, under every tolerance norm."""
+    df = pl.DataFrame({"from": ["A", "A", "B", "C", "D"], "to": ["B", "C", "C", "A", "B"]})
+
+    def diagnostics(**kwargs: object) -> tuple:
+        result = df.select(page_rank(pl.col("from"), pl.col("to"), nodes=True, **kwargs).alias("pagerank"))
+        row = result.unnest("pagerank").row(0, named=True)
+        return row["iterations"], row["residual"], row["converged"]
+
+    iterations, residual, converged = diagnostics(tolerance_norm="l1")
+    assert (iterations, converged) == (28, True)
+    assert residual < 1e-6
+    # The largest change of a single node is below the sum of all changes, so it stops sooner
+    assert diagnostics(tolerance_norm="inf")[0] < iterations
+    assert diagnostics(tolerance_norm="l2")[0] < iterations
+
+    iterations, residual, converged = diagnostics(max_iterations=5)
+    assert (iterations, converged) == (5, False)
+    assert residual == pytest.approx(0.110926, abs=1e-6)
+
+
+def test_page_rank_methods() -> None:
+    """Test that every page_rank method converges to the scores of the power iteration."""
+    df = pl.DataFrame(
+        {
+            "from": ["A", "A", "B", "C", "D", "E", "E"],
+            "to": ["B", "C", "C", "A", "B", "A", "F"],
+            "weight": [1.0, 2.0, 1.0, 3.0, 1.0, 0.5, 1.5],
+        }
+    )
+
+    def ranks(method: str) -> pl.DataFrame:
+        result = df.select(
+            page_rank(
+                pl.col("from"), pl.col("to"), weights=pl.col("weight"), nodes=True, method=method
+            ).alias("pagerank")
+        )
+        return result.unnest("pagerank")
+
+    power = ranks("power")
+    for method in ["push", "gauss_seidel", "parallel"]:
+        result = ranks(method)
+        assert result["node"].to_list() == power["node"].to_list()
+        assert result["score"].to_list() == pytest.approx(power["score"].to_list(), abs=1e-5)
+        assert result["converged"].all()
+    assert ranks("gauss_seidel")["iterations"][0] <= power["iterations"][0]
+
+
+def test_hits() -> None:
+    """Test that hits scores hubs and authorities of a bipartite graph, matching networkx."""
+    df = pl.DataFrame({"from": ["u1", "u1", "u2", "u3", "u3"], "to": ["p1", "p2", "p1", "p1", "p3"]})
+
+    result = df.select(hits(pl.col("from"), pl.col("to")).alias("hits")).unnest("hits")
+
+    # networkx.hits(nx.DiGraph(edges)), nodes in order of first appearance
+    assert result["node"].to_list() == ["u1", "p1", "p2", "u2", "u3", "p3"]
+    assert result["hub"].to_list() == pytest.approx([0.366025, 0.0, 0.0, 0.267949, 0.366025, 0.0], abs=1e-6)
+    assert result["authority"].to_list() == pytest.approx([0.0, 0.57735, 0.211325, 0.0, 0.0, 0.211325], abs=1e-6)
+
+
+def test_page_rank_weighted() -> None:
+    """Test that weighted page_rank splits rank in proportion to edge weights, matching networkx, and rejects negative or infinite weights."""
+    df = pl.DataFrame(
         {
-            "rank": [
-                0.012500000000000002,
-                0.023125000000000007,
-                0.032156250000000004,
-                0.012500000000000002,
-                0.023125000000000007,
-                0.032156250000000004,
-                0.012500000000000002,
-                0.012500000000000002,
-                0.012500000000000002,
-            ]
+            "from": ["A", "A", "B", "C", "C"],
+            "to": ["B", "C", "C", "A", "D"],
+            "weight": [3.0, 1.0, 1.0, 1.0, 2.0],
         }
     )
-    assert result_df.equals(expected_df), "The rank values were not calculated as expected."
+
+    result = df.select(
+        page_rank(pl.col("from"), pl.col("to"), nodes=True, weights=pl.col("weight")).alias("pagerank")
+    ).unnest("pagerank")
+
+    # networkx.pagerank(nx.MultiDiGraph(edges), weight="weight")
+    assert result["node"].to_list() == ["A", "B", "C", "D"]
+    assert result["score"].to_list() == pytest.approx([0.187048, 0.215734, 0.319613, 0.277605], abs=1e-5)
+
+    negative = df.with_columns(pl.Series("weight", [3.0, -1.0, 1.0, 1.0, 2.0]))
+    with pytest.raises(pl.exceptions.ComputeError, match="row 1 has weight -1"):
+        negative.select(page_rank(pl.col("from"), pl.col("to"), weights=pl.col("weight")))
+
+    infinite = df.with_columns(pl.Series("weight", [3.0, 1.0, float("inf"), 1.0, 2.0]))
+    with pytest.raises(pl.exceptions.ComputeError, match="row 2 has weight inf"):
+        infinite.select(page_rank(pl.col("from"), pl.col("to"), weights=pl.col("weight")))
+
+    with pytest.raises(pl.exceptions.ShapeError, match="weights and edges differ in length"):
+        df.select(page_rank(pl.col("from"), pl.col("to"), weights=pl.lit(1.0)))
+
+
+def test_page_rank_personalized() -> None:
+    """Test that personalized page_rank teleports to the seed nodes, matching networkx."""
+    df = pl.DataFrame({"from": ["A", "B", "C", "C", "E"], "to": ["B", "C", "A", "D", "D"]})
+
+    def scores(**kwargs: object) -> list:
+        result = df.select(page_rank(pl.col("from"), pl.col("to"), nodes=True, **kwargs).alias("pagerank"))
+        return result.unnest("pagerank")["score"].to_list()
+
+    # networkx.pagerank(nx.MultiDiGraph(edges), personalization=...), nodes A, B, C, D, E
+    assert scores(personalization=["A"]) == pytest.approx([0.347274, 0.295184, 0.250907, 0.106634, 0.0], abs=1e-5)
+    assert scores(personalization=["A", "E", "X"], personalization_weights=[3.0, 1.0, 5.0]) == pytest.approx(
+        [0.3024, 0.25704, 0.218485, 0.152227, 0.069848], abs=1e-5
+    )
+
+    with pytest.raises(pl.exceptions.ComputeError, match="no weight on any node"):
+        df.select(page_rank(pl.col("from"), pl.col("to"), personalization=["X"]))
+
+
+def test_page_rank_partition_by() -> None:
+    """Test that partitioned page_rank ranks every partition as a graph of its own."""
+    df = pl.DataFrame(
+        {
+            "part": ["x", "y", "x", "y", None, "x"],
+            "from": ["A", "A", "B", "B", "A", "C"],
+            "to": ["B", "B", "C", "A", "B", "A"],
+        }
+    )
+    result = df.select(page_rank(pl.col("from"), pl.col("to"), partition_by=pl.col("part")).alias("pagerank"))
+
+    # Every partition matches page_rank of its rows alone
+    for part in ["x", "y", None]:
+        mask = df["part"].is_null() if part is None else df["part"] == part
+        expected = df.filter(mask).select(page_rank(pl.col("from"), pl.col("to")).alias("pagerank"))
+        assert result.filter(mask)["pagerank"].to_list() == pytest.approx(expected["pagerank"].to_list())
+
+    with pytest.raises(ValueError, match="partition_by"):
+        page_rank(pl.col("from"), pl.col("to"), nodes=True, partition_by=pl.col("part"))
+
+    # A null node is reported at its row in the input, not its row within the partition
+    with_null = df.with_columns(pl.Series("from", ["A", "A", "B", None, "A", "C"]))
+    with pytest.raises(pl.exceptions.ComputeError, match="row 3 has a null 'from' node"):
+        with_null.select(page_rank(pl.col("from"), pl.col("to"), partition_by=pl.col("part"), null_policy="error"))
+
+
+def test_page_rank_partition_by_large() -> None:
+    """Test that partitioned page_rank matches page_rank over the partitions on inputs that are ranked in parallel."""
+    # More rows than the parallel threshold of 65536
+    n = 100_000
+    df = pl.DataFrame(
+        {
+            "part": [i % 7 for i in range(n)],
+            "from": [i % 1_000 for i in range(n)],
+            "to": [(i * 31 + 5) % 1_000 for i in range(n)],
+        }
+    )
+
+    result = df.select(page_rank(pl.col("from"), pl.col("to"), partition_by=pl.col("part")).alias("pagerank"))
+    expected = df.select(page_rank(pl.col("from"), pl.col("to")).over("part").alias("pagerank"))
+    assert result["pagerank"].to_list() == pytest.approx(expected["pagerank"].to_list())
 
 
 def test_graph_solver() -> None:
@@ -51,6 +214,233 @@ def test_graph_solver() -> None:
     assert result_df.equals(expected_df), "The graph_solver did not assign the expected group IDs."
 
 
+def test_graph_solver_weight_threshold() -> None:
+    """Test that edges failing the weight threshold are not unioned but every row keeps a group."""
+    df = pl.DataFrame(
+        {"from": ["A", "B", "C", "D"], "to": ["B", "C", "D", "E"], "weight": [0.9, 0.2, 0.05, 0.8]}
+    )
+    result_df = df.select(
+        graph_solver(pl.col("from"), pl.col("to"), pl.col("weight"), weight_threshold=0.3).alias("group")
+    )
+    expected_df = pl.DataFrame({"group": [1, 1, 2, 3]})
+
+    assert result_df.equals(expected_df), "The weight threshold was not applied as expected."
+
+    result_df = df.select(
+        graph_solver(pl.col("from"), pl.col("to"), pl.col("weight"), weight_threshold=0.3, comparison="<").alias(
+            "group"
+        )
+    )
+    expected_df = pl.DataFrame({"group": [1, 2, 2, 2]})
+
+    assert result_df.equals(expected_df), "The comparison kwarg was not applied as expected."
+
+    # The weights need one value per row
+    with pytest.raises(pl.exceptions.ShapeError, match="weights and edges differ in length"):
+        df.select(graph_solver(pl.col("from"), pl.col("to"), pl.lit(0.5)))
+
+
+def test_super_merger_weighted_keeps_rows() -> None:
+    """Test that super_merger_weighted keeps rows below the threshold with their own group."""
+    df = pl.DataFrame(
+        {"from": ["A", "B", "C", "D", "E"], "to": ["B", "C", "D", "E", "A"], "weight": [0.9, 0.2, 0.05, 0.8, 0.3]}
+    )
+
+    result_df = super_merger_weighted(df, "from", "to", "weight", weight_threshold=0.3)
+
+    assert result_df.height == df.height
+    assert result_df["group"].to_list() == [1, 1, 2, 1, 1]
+
+
+def test_strongly_connected_components() -> None:
+    """Test that strongly connected components respect edge direction and flag cycle edges."""
+    df = pl.DataFrame({"from": ["A", "B", "C", "C", "D", "E"], "to": ["B", "C", "A", "D", "E", "E"]})
+
+    result = df.select(strongly_connected_components(pl.col("from"), pl.col("to")).alias("scc")).unnest("scc")
+
+    assert result["component"].to_list() == [1, 1, 1, 1, 2, 3]
+    assert result["in_cycle"].to_list() == [True, True, True, False, False, True]
+
+
+def test_connected_components() -> None:
+    """Test that weak and strong component ids and sizes are returned together."""
+    df = pl.DataFrame({"from": ["A", "B", "C", "C", "D", "F"], "to": ["B", "C", "A", "D", "E", "G"]})
+
+    result = df.select(connected_components(pl.col("from"), pl.col("to")).alias("components")).unnest("components")
+
+    assert result["weak_component"].to_list() == [1, 1, 1, 1, 1, 2]
+    assert result["strong_component"].to_list() == [1, 1, 1, 1, 2, 4]
+    assert result["component_size"].to_list() == [5, 5, 5, 5, 5, 2]
+
+
+def test_graph_component_summary() -> None:
+    """Test that the component summary returns one row per component with its statistics."""
+    df = pl.DataFrame({"from": ["A", "B", "C", "D", "F"], "to": ["B", "C", "A", "E", "G"]})
+
+    result = df.select(graph_component_summary(pl.col("from"), pl.col("to")).alias("summary")).unnest("summary")
+
+    assert result["group"].to_list() == [1, 2, 3]
+    assert result["num_nodes"].to_list() == [3, 2, 2]
+    assert result["num_edges"].to_list() == [3, 1, 1]
+    assert result["density"].to_list() == [1.0, 1.0, 1.0]
+    assert result["representative"].to_list() == ["A", "D", "F"]
+    assert result["is_tree"].to_list() == [False, True, True]
+
+
+def test_graph_solver_nodes() -> None:
+    """Test that the node-level output contains every distinct node, including destination-only nodes."""
+    df = pl.DataFrame({"from": ["A", "B", "D"], "to": ["B", "C", "E"]})
+
+    result = df.select(graph_solver_nodes(pl.col("from"), pl.col("to")).alias("nodes")).unnest("nodes")
+    expected_df = pl.DataFrame({"node": ["A", "B", "C", "D", "E"], "group": [1, 1, 1, 2, 2]})
+
+    assert result.equals(expected_df), "The graph_solver_nodes did not return the expected node groups."
+
+
+def test_graph_solver_integer_nodes() -> None:
+    """Test that integer node columns are grouped by value and nodes keep their integer dtype."""
+    df = pl.DataFrame({"from": [10, 20, 40], "to": [20, 30, 50]}, schema={"from": pl.Int32, "to": pl.Int64})
+
+    groups = df.select(graph_solver(pl.col("from"), pl.col("to")).alias("group"))
+    assert groups["group"].to_list() == [1, 1, 2]
+
+    nodes = df.select(graph_solver_nodes(pl.col("from"), pl.col("to")).alias("nodes")).unnest("nodes")
+    assert nodes.schema["node"] == pl.Int64
+    assert nodes["node"].to_list() == [10, 20, 30, 40, 50]
+
+    stable = df.select(graph_solver(pl.col("from"), pl.col("to"), stable_ids=True).alias("group"))
+    stable_strings = df.select(
+        graph_solver(pl.col("from").cast(pl.String), pl.col("to").cast(pl.String), stable_ids=True).alias("group")
+    )
+    assert stable.equals(stable_strings), "Stable ids should not depend on the dtype of the node columns."
+
+
+def test_graph_solver_null_policy() -> None:
+    """Test that rows with a null node are dropped, rejected or isolated without adding an edge."""
+    df = pl.DataFrame({"from": ["A", "B", None, None], "to": [None, "C", "D", None]})
+
+    dropped = df.select(graph_solver(pl.col("from"), pl.col("to")).alias("group"))
+    assert dropped["group"].to_list() == [0, 1, 0, 0]
+
+    isolated = df.select(graph_solver(pl.col("from"), pl.col("to"), null_policy="isolate").alias("group"))
+    assert isolated["group"].to_list() == [1, 2, 3, 0]
+
+    # An isolated node has no edge, so it neither lies on a cycle nor breaks a tree
+    scc = df.select(
+        strongly_connected_components(pl.col("from"), pl.col("to"), null_policy="isolate").alias("scc")
+    ).unnest("scc")
+    assert scc["in_cycle"].to_list() == [False, False, False, False]
+    summary = df.select(
+        graph_component_summary(pl.col("from"), pl.col("to"), null_policy="isolate").alias("summary")
+    ).unnest("summary")
+    assert summary["num_edges"].to_list() == [0, 1, 0]
+    assert summary["is_tree"].to_list() == [True, True, True]
+
+    with pytest.raises(pl.exceptions.ComputeError, match="row 0 has a null 'to' node"):
+        df.select(graph_solver(pl.col("from"), pl.col("to"), null_policy="error"))
+
+
+def test_graph_solver_stable_ids() -> None:
+    """Test that stable ids don't change when rows are reordered or other components are removed."""
+    df = pl.DataFrame({"from": ["A", "B", "E", "F", "X"], "to": ["B", "C", "F", "G", "Y"]})
+
+    result = df.with_columns(graph_solver(pl.col("from"), pl.col("to"), stable_ids=True).alias("group"))
+    reversed_result = (
+        df.reverse()
+        .filter(pl.col("from") != "X")
+        .with_columns(graph_solver(pl.col("from"), pl.col("to"), stable_ids=True).alias("group"))
+    )
+
+    joined = result.join(reversed_result, on=["from", "to"], suffix="_reversed")
+    assert joined.height == 4
+    assert (joined["group"] == joined["group_reversed"]).all()
+    assert result["group"].n_unique() == 3
+
+
+def test_graph_solver_large_input() -> None:
+    """Test the multi-threaded path used for large edge lists numbers groups like the sequential one."""
+    num_rows = 1_500_000
+    df = pl.DataFrame({"from": pl.int_range(0, num_rows, eager=True)}).with_columns(
+        (pl.col("from") % 1000).alias("to")
+    )
+
+    result = df.select(graph_solver(pl.col("from"), pl.col("to")).alias("group"))
+
+    assert result["group"].equals((df["from"] % 1000 + 1).cast(pl.UInt64).alias("group"))
+
+
+def test_graph_analytics_distinct_nodes() -> None:
+    """Test that node ids are wide enough when a graph has more nodes than edges."""
+    num_rows = 40_000
+    df = pl.DataFrame({"from": pl.int_range(0, num_rows, eager=True)}).with_columns(
+        (pl.col("from") + num_rows).alias("to")
+    )
+
+    groups = df.select(graph_solver(pl.col("from"), pl.col("to")).alias("group"))
+    assert groups["group"].n_unique() == num_rows
+
+    ranks = df.select(page_rank(pl.col("from"), pl.col("to")).alias("rank"))
+    assert ranks["rank"].len() == num_rows
+
+
+def test_graph_solver_incremental() -> None:
+    """Test that new edges are merged into a previous node table and merged groups are reported."""
+    history = pl.DataFrame({"from": ["A", "C", "X"], "to": ["B", "D", "Y"]})
+    state = history.select(graph_solver_nodes(pl.col("from"), pl.col("to")).alias("nodes")).unnest("nodes")
+
+    delta = pl.DataFrame({"from": ["B", "E"], "to": ["C", "F"]})
+    result = delta.select(
+        graph_solver_incremental(pl.col("from"), pl.col("to"), state["node"], state["group"]).alias("nodes")
+    ).unnest("nodes")
+
+    expected_df = pl.DataFrame(
+        {
+            "node": ["A", "B", "C", "D", "X", "Y", "E", "F"],
+            "group": [1, 1, 1, 1, 3, 3, 4, 4],
+            "previous_group": [1, 1, 2, 2, 3, 3, None, None],
+        }
+    )
+    assert result.equals(expected_df), "The incremental update did not return the expected node groups."
+
+    # The incremental result matches a full recomputation
+    full = pl.concat([history, delta]).select(graph_solver_nodes(pl.col("from"), pl.col("to")).alias("nodes"))
+    assert full.unnest("nodes")["group"].n_unique() == result["group"].n_unique()
+
+    # New components get ids up to the largest UInt64 and raise an error beyond it
+    state = pl.DataFrame({"node": ["A"], "group": [2**64 - 2]}, schema={"node": pl.String, "group": pl.UInt64})
+    delta = pl.DataFrame({"from": ["E", "G"], "to": ["F", "H"]})
+
+    def incremental(edges: pl.DataFrame) -> pl.DataFrame:
+        return edges.select(
+            graph_solver_incremental(pl.col("from"), pl.col("to"), state["node"], state["group"]).alias("nodes")
+        ).unnest("nodes")
+
+    assert incremental(delta.head(1))["group"].to_list() == [2**64 - 2, 2**64 - 1, 2**64 - 1]
+    with pytest.raises(pl.exceptions.ComputeError, match="no group ids left"):
+        incremental(delta)
+
+
+def test_graph_component_lineage() -> None:
+    """Test that merges, splits, new and removed nodes are reported in the lineage table."""
+    state = pl.DataFrame({"node": ["A", "B", "C", "D", "X", "Y", "Z", "Q"], "group": [1, 1, 2, 2, 3, 3, 3, 4]})
+    edges = pl.DataFrame({"from": ["A", "B", "C", "X", "Z"], "to": ["B", "C", "D", "Y", "W"]})
+
+    result = edges.select(
+        graph_component_lineage(pl.col("from"), pl.col("to"), state["node"], state["group"]).alias("lineage")
+    ).unnest("lineage")
+
+    expected_df = pl.DataFrame(
+        {
+            "old_group": [1, 2, 3, 3, 4, None],
+            "new_group": [1, 1, 2, 3, None, 3],
+            "num_nodes": [2, 2, 2, 1, 1, 1],
+            "is_merge": [True, True, False, False, False, False],
+            "is_split": [False, False, True, True, False, False],
+        }
+    )
+    assert result.equals(expected_df), "The lineage table was not calculated as expected."
+
+
 def test_super_merger() -> None:
     """Test that the supermerger function correctly adds group IDs to a DataFrame."""
     df = pl.DataFrame({"from": ["A", "B", "C", "E", "F", "G", "I"], "to": ["B", "C", "D", "F", "G", "J", "K"]})
@@ -189,6 +579,64 @@ def test_betweenness_empty_graph() -> None:
     assert len(result) == 0
 
 
+def test_graph_analytics_integer_nodes() -> None:
+    """Test that shortest paths and betweenness centrality return integer nodes in their input dtype."""
+    df = pl.DataFrame({"from": [1, 2], "to": [2, 3], "weight": [1.0, 2.0]})
+
+    centrality = df.select(
+        betweenness_centrality(pl.col("from"), pl.col("to"), normalized=True, directed=False).alias("centrality")
+    ).unnest("centrality")
+    assert centrality.schema["node"] == pl.Int64
+    assert centrality["node"].to_list() == [1, 2, 3]
+    assert centrality["centrality"].to_list() == [0.0, 1.0, 0.0]
+
+    paths = df.select(
+        calculate_shortest_path(pl.col("from"), pl.col("to"), pl.col("weight"), directed=False).alias("paths")
+    ).unnest("paths")
+    assert paths.schema["from"] == pl.Int64
+    assert paths.rows() == [(1, 2, 1.0), (1, 3, 3.0), (2, 3, 2.0)]
+
+    # Pairs are ordered by node value, not by the decimal form of the nodes
+    numeric = pl.DataFrame({"from": [10, 2], "to": [2, 1], "weight": [1.0, 1.0]})
+    paths = numeric.select(
+        calculate_shortest_path(pl.col("from"), pl.col("to"), pl.col("weight"), directed=False).alias("paths")
+    ).unnest("paths")
+    assert paths.rows() == [(1, 2, 1.0), (1, 10, 2.0), (2, 10, 1.0)]
+
+
+def test_graph_analytics_multi_edges() -> None:
+    """Test that dedupe_edges and self_loops remove repeated edges and self-loops before ranking nodes."""
+    # A square with the A-B side repeated in both orientations
+    square = pl.DataFrame({"from": ["A", "B", "B", "A", "C"], "to": ["B", "A", "D", "C", "D"]})
+
+    def centrality(**kwargs: bool) -> dict:
+        result = square.select(
+            betweenness_centrality(pl.col("from"), pl.col("to"), directed=False, **kwargs).alias("centrality")
+        ).unnest("centrality")
+        return dict(result.iter_rows())
+
+    repeated = centrality()
+    assert repeated["B"] > repeated["C"]
+    deduped = centrality(dedupe_edges=True)
+    assert deduped["B"] == deduped["C"]
+
+    # Deduplicated pairs keep their lightest edge
+    heavy_first = pl.DataFrame({"from": ["A", "A"], "to": ["B", "B"], "weight": [5.0, 1.0]})
+    paths = heavy_first.select(
+        calculate_shortest_path(
+            pl.col("from"), pl.col("to"), pl.col("weight"), directed=False, dedupe_edges=True
+        ).alias("paths")
+    ).unnest("paths")
+    assert paths.rows() == [("A", "B", 1.0)]
+
+    loops = pl.DataFrame({"from": ["A", "A"], "to": ["A", "B"]})
+    kept = loops.select(page_rank(pl.col("from"), pl.col("to")).alias("rank"))["rank"]
+    dropped = loops.select(page_rank(pl.col("from"), pl.col("to"), self_loops="drop").alias("rank"))["rank"]
+    single = pl.DataFrame({"from": ["A"], "to": ["B"]}).select(page_rank(pl.col("from"), pl.col("to")))
+    assert dropped.to_list() == [single.item()] * 2
+    assert kept[0] > dropped[0]
+
+
 def test_basic_association_rules() -> None:
     """
     Test the graph_association_rules function with a basic set of transactions.
@@ -392,6 +840,9 @@ def test_calculate_shortest_path() -> None:
     for (start, end), distance in expected_paths.items():
         assert abs(actual_paths[(start, end)] - distance) < 0.1
 
+    with pytest.raises(pl.exceptions.ShapeError, match="weights and edges differ in length"):
+        df.select(calculate_shortest_path(pl.col("from"), pl.col("to"), pl.lit(1.0)))
+
 
 def test_directed_path() -> None:
     """